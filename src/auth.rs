@@ -0,0 +1,91 @@
+//! Optional shared-secret gate for exposing the stdio transport beyond a
+//! locally-trusted process. When a secret is configured (inline via
+//! `TOAD_MCP_SECRET`, or file-based via `TOAD_MCP_SECRET_FILE`), every tool
+//! call is refused with an error until the client calls `authenticate` with
+//! a matching token. With neither env var set, the gate is a no-op so
+//! existing local/trusted deployments are unaffected.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use toad_core::ToadError;
+
+struct AuthGateInner {
+    secret: Option<String>,
+    authenticated: AtomicBool,
+}
+
+/// Shared handle held by `ToadService`; cheap to clone. The `authenticated`
+/// flag is process-wide rather than per-client, which matches the stdio
+/// transport's one-client-per-process model.
+#[derive(Clone)]
+pub struct AuthGate(Arc<AuthGateInner>);
+
+impl AuthGate {
+    /// Resolves the configured secret from `TOAD_MCP_SECRET` (inline) or
+    /// `TOAD_MCP_SECRET_FILE` (a path to a file holding the secret), erroring
+    /// if both are set since it's ambiguous which one should win.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let inline = std::env::var("TOAD_MCP_SECRET").ok();
+        let file_path = std::env::var("TOAD_MCP_SECRET_FILE").ok();
+
+        let secret = match (inline, file_path) {
+            (Some(_), Some(_)) => anyhow::bail!(
+                "Both TOAD_MCP_SECRET and TOAD_MCP_SECRET_FILE are set; configure only one"
+            ),
+            (Some(s), None) => Some(s),
+            (None, Some(path)) => {
+                let raw = std::fs::read_to_string(&path).map_err(|e| {
+                    anyhow::anyhow!("Failed to read TOAD_MCP_SECRET_FILE '{}': {}", path, e)
+                })?;
+                Some(raw.trim().to_string())
+            }
+            (None, None) => None,
+        };
+
+        let authenticated = secret.is_none();
+        Ok(Self(Arc::new(AuthGateInner {
+            secret,
+            authenticated: AtomicBool::new(authenticated),
+        })))
+    }
+
+    /// True when no secret is configured, or the client has already presented a matching token.
+    pub fn is_authenticated(&self) -> bool {
+        self.0.authenticated.load(Ordering::Relaxed)
+    }
+
+    /// Checks `token` against the configured secret, opening the gate on a match.
+    /// Always succeeds when no secret is configured.
+    pub fn authenticate(&self, token: &str) -> bool {
+        match &self.0.secret {
+            None => true,
+            Some(expected) => {
+                if constant_time_eq(expected.as_bytes(), token.as_bytes()) {
+                    self.0.authenticated.store(true, Ordering::Relaxed);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    pub fn ensure_authenticated(&self) -> Result<(), ToadError> {
+        if self.is_authenticated() {
+            Ok(())
+        } else {
+            Err(ToadError::Other(
+                "Not authenticated: call `authenticate` with a valid token first".to_string(),
+            ))
+        }
+    }
+}
+
+/// Fixed-time comparison so a mismatched-length or wrong-byte secret can't be
+/// timed to leak how many leading bytes matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,237 @@
+//! Declarative workload harness used by `run_workload` to exercise the
+//! analytics tools (`analyze_velocity`, `analyze_debt`, `analyze_health`,
+//! etc.) against a fixed registry and report wall-clock cost, so a growing
+//! registry's scanning overhead shows up as a diffable report instead of an
+//! anecdote reported by a user.
+
+use serde::{Deserialize, Serialize};
+
+/// On-disk schema for a workload file: an ordered list of analytics
+/// invocations, each optionally repeated to smooth out noise, plus an
+/// optional path to a previously saved `WorkloadReport` to regress against.
+#[derive(Deserialize)]
+pub struct Workload {
+    pub name: String,
+    pub steps: Vec<WorkloadStep>,
+    pub baseline: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WorkloadStep {
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+    #[serde(default = "default_repeat")]
+    pub repeat: usize,
+}
+
+fn default_repeat() -> usize {
+    1
+}
+
+/// Timing distribution for a single step, built from one wall-clock sample
+/// per repeat. `result_bytes` is the serialized size of the last sample's
+/// result, used as a rough proxy for response payload cost. `peak_rss_delta_kb`
+/// is an approximate allocation proxy: process RSS is sampled at the start of
+/// the step and again after each repeat, and this is the largest increase
+/// seen. It is not a true peak-allocation trace (no allocator hook), just a
+/// before/after-timing snapshot, and is `None` on platforms where `/proc` is
+/// unavailable.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    pub tool: String,
+    pub samples_ms: Vec<f64>,
+    pub min_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub result_bytes: usize,
+    pub peak_rss_delta_kb: Option<i64>,
+}
+
+/// Run-level rollup over every step, so a report is skimmable without
+/// summing `steps` by hand.
+#[derive(Serialize, Deserialize)]
+pub struct WorkloadSummary {
+    pub step_count: usize,
+    pub total_median_ms: f64,
+    pub total_result_bytes: usize,
+    pub peak_rss_delta_kb: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WorkloadReport {
+    pub name: String,
+    pub steps: Vec<StepReport>,
+    pub summary: WorkloadSummary,
+}
+
+/// Builds a `StepReport` from raw millisecond samples, sorting them to
+/// derive min/median/p95/max.
+pub fn summarize(
+    tool: String,
+    mut samples_ms: Vec<f64>,
+    result_bytes: usize,
+    peak_rss_delta_kb: Option<i64>,
+) -> StepReport {
+    samples_ms.sort_by(|a, b| a.total_cmp(b));
+    let min_ms = samples_ms.first().copied().unwrap_or(0.0);
+    let max_ms = samples_ms.last().copied().unwrap_or(0.0);
+    let median_ms = percentile(&samples_ms, 0.5);
+    let p95_ms = percentile(&samples_ms, 0.95);
+
+    StepReport {
+        tool,
+        samples_ms,
+        min_ms,
+        median_ms,
+        p95_ms,
+        max_ms,
+        result_bytes,
+        peak_rss_delta_kb,
+    }
+}
+
+/// Rolls a set of `StepReport`s up into a `WorkloadSummary`.
+pub fn summarize_run(steps: &[StepReport]) -> WorkloadSummary {
+    WorkloadSummary {
+        step_count: steps.len(),
+        total_median_ms: steps.iter().map(|s| s.median_ms).sum(),
+        total_result_bytes: steps.iter().map(|s| s.result_bytes).sum(),
+        peak_rss_delta_kb: steps.iter().filter_map(|s| s.peak_rss_delta_kb).max(),
+    }
+}
+
+/// Reads the current process's resident set size in KB from `/proc/self/status`.
+/// Returns `None` on non-Linux platforms or if the file can't be parsed.
+pub fn read_rss_kb() -> Option<i64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status.lines().find_map(|line| {
+        let rest = line.strip_prefix("VmRSS:")?;
+        rest.trim().split_whitespace().next()?.parse().ok()
+    })
+}
+
+/// Nearest-rank percentile over an already-sorted sample set.
+fn percentile(sorted_samples: &[f64], p: f64) -> f64 {
+    if sorted_samples.is_empty() {
+        return 0.0;
+    }
+    let rank = (p * (sorted_samples.len() - 1) as f64).round() as usize;
+    sorted_samples[rank.min(sorted_samples.len() - 1)]
+}
+
+#[derive(Serialize)]
+pub struct StepComparison {
+    pub tool: String,
+    pub baseline_median_ms: f64,
+    pub current_median_ms: f64,
+    pub pct_change: f64,
+    pub regressed: bool,
+}
+
+/// Compares `current` steps against a saved `baseline` report position by
+/// position (workloads are ordered, so step N is assumed to be the same
+/// invocation across runs), flagging any step whose median latency grew by
+/// more than `threshold_pct`.
+pub fn compare_to_baseline(
+    current: &[StepReport],
+    baseline: &WorkloadReport,
+    threshold_pct: f64,
+) -> Vec<StepComparison> {
+    current
+        .iter()
+        .zip(baseline.steps.iter())
+        .map(|(cur, base)| {
+            let pct_change = if base.median_ms > 0.0 {
+                ((cur.median_ms - base.median_ms) / base.median_ms) * 100.0
+            } else {
+                0.0
+            };
+            StepComparison {
+                tool: cur.tool.clone(),
+                baseline_median_ms: base.median_ms,
+                current_median_ms: cur.median_ms,
+                pct_change,
+                regressed: pct_change > threshold_pct,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_nearest_rank_on_sorted_samples() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&samples, 0.0), 1.0);
+        assert_eq!(percentile(&samples, 0.5), 3.0);
+        assert_eq!(percentile(&samples, 1.0), 5.0);
+    }
+
+    #[test]
+    fn percentile_on_empty_samples_is_zero() {
+        assert_eq!(percentile(&[], 0.5), 0.0);
+    }
+
+    #[test]
+    fn summarize_derives_min_median_p95_max_from_unsorted_samples() {
+        let report = summarize("analyze_debt".to_string(), vec![30.0, 10.0, 20.0], 128, Some(512));
+        assert_eq!(report.min_ms, 10.0);
+        assert_eq!(report.median_ms, 20.0);
+        assert_eq!(report.max_ms, 30.0);
+        assert_eq!(report.result_bytes, 128);
+        assert_eq!(report.peak_rss_delta_kb, Some(512));
+    }
+
+    #[test]
+    fn summarize_run_rolls_up_medians_bytes_and_peak_rss() {
+        let steps = vec![
+            summarize("a".to_string(), vec![10.0], 100, Some(50)),
+            summarize("b".to_string(), vec![20.0], 200, Some(150)),
+        ];
+        let rollup = summarize_run(&steps);
+        assert_eq!(rollup.step_count, 2);
+        assert_eq!(rollup.total_median_ms, 30.0);
+        assert_eq!(rollup.total_result_bytes, 300);
+        assert_eq!(rollup.peak_rss_delta_kb, Some(150));
+    }
+
+    #[test]
+    fn summarize_run_omits_peak_rss_when_every_step_lacks_it() {
+        let steps = vec![summarize("a".to_string(), vec![10.0], 100, None)];
+        assert_eq!(summarize_run(&steps).peak_rss_delta_kb, None);
+    }
+
+    #[test]
+    fn compare_to_baseline_flags_a_regression_over_threshold() {
+        let baseline_steps = vec![summarize("a".to_string(), vec![100.0], 10, None)];
+        let baseline = WorkloadReport {
+            name: "baseline".to_string(),
+            summary: summarize_run(&baseline_steps),
+            steps: baseline_steps,
+        };
+        let current = vec![summarize("a".to_string(), vec![130.0], 10, None)];
+
+        let comparisons = compare_to_baseline(&current, &baseline, 20.0);
+        assert_eq!(comparisons.len(), 1);
+        assert!(comparisons[0].regressed);
+        assert!((comparisons[0].pct_change - 30.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn compare_to_baseline_does_not_flag_improvements() {
+        let baseline_steps = vec![summarize("a".to_string(), vec![100.0], 10, None)];
+        let baseline = WorkloadReport {
+            name: "baseline".to_string(),
+            summary: summarize_run(&baseline_steps),
+            steps: baseline_steps,
+        };
+        let current = vec![summarize("a".to_string(), vec![80.0], 10, None)];
+
+        let comparisons = compare_to_baseline(&current, &baseline, 20.0);
+        assert!(!comparisons[0].regressed);
+    }
+}
@@ -0,0 +1,102 @@
+//! Minimal GitHub REST API client used by context-import tools.
+//!
+//! Handles pagination via the `Link` response header. When a 403 reports the
+//! rate limit as exhausted it backs off and retries once; if the limit is
+//! still exhausted, the import fails.
+
+use serde::Deserialize;
+use std::thread;
+use std::time::Duration;
+use toad_core::ToadError;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GithubRepo {
+    pub name: String,
+    pub full_name: String,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub fork: bool,
+}
+
+/// Fetches every repository for an org or user, following `Link: rel="next"`
+/// pagination headers and retrying once on a 403 with
+/// `X-RateLimit-Remaining: 0`. A name the `/orgs/` endpoint doesn't know
+/// (404 on the first page) is retried as a user account.
+pub fn list_org_repos(org: &str, token: Option<&str>) -> Result<Vec<GithubRepo>, ToadError> {
+    const MAX_RATE_LIMIT_RETRIES: usize = 1;
+
+    let mut repos = Vec::new();
+    let orgs_url = format!("https://api.github.com/orgs/{}/repos?per_page=100", org);
+    let mut url = orgs_url.clone();
+    let mut rate_limit_retries = 0;
+
+    loop {
+        let mut req = ureq::get(&url)
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "toad-mcp");
+        if let Some(t) = token {
+            req = req.set("Authorization", &format!("Bearer {}", t));
+        }
+
+        let resp = match req.call() {
+            Ok(r) => r,
+            Err(ureq::Error::Status(403, r)) => {
+                let exhausted = r.header("x-ratelimit-remaining") == Some("0");
+                if exhausted && rate_limit_retries < MAX_RATE_LIMIT_RETRIES {
+                    rate_limit_retries += 1;
+                    thread::sleep(Duration::from_secs(2));
+                    continue;
+                }
+                return Err(ToadError::Other(format!(
+                    "GitHub API returned 403 for '{}'{}",
+                    org,
+                    if exhausted { " (rate limited)" } else { "" }
+                )));
+            }
+            Err(ureq::Error::Status(404, _)) if url == orgs_url => {
+                url = format!("https://api.github.com/users/{}/repos?per_page=100", org);
+                continue;
+            }
+            Err(e) => return Err(ToadError::Other(format!("GitHub API request failed: {}", e))),
+        };
+
+        rate_limit_retries = 0;
+        let next = resp
+            .header("link")
+            .and_then(parse_next_link)
+            .map(|s| s.to_string());
+
+        let page: Vec<GithubRepo> = resp
+            .into_json()
+            .map_err(|e| ToadError::Other(format!("Failed to parse GitHub response: {}", e)))?;
+        repos.extend(page);
+
+        match next {
+            Some(n) => url = n,
+            None => break,
+        }
+    }
+
+    Ok(repos)
+}
+
+fn parse_next_link(header: &str) -> Option<&str> {
+    header.split(',').find_map(|part| {
+        let mut segments = part.split(';');
+        let url_part = segments.next()?.trim();
+        let is_next = segments.any(|s| s.trim() == "rel=\"next\"");
+        if is_next {
+            Some(url_part.trim_start_matches('<').trim_end_matches('>'))
+        } else {
+            None
+        }
+    })
+}
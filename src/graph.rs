@@ -0,0 +1,247 @@
+//! Dependency-graph helpers shared by the build-order and migration-order tools.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Result of a Kahn's-algorithm topological sort: projects grouped by the
+/// level at which they became ready, plus any nodes left over due to a cycle.
+pub struct TopoResult {
+    pub levels: Vec<Vec<String>>,
+    pub cycle: Vec<String>,
+}
+
+/// Runs Kahn's algorithm over an adjacency map of `node -> dependencies`.
+/// Nodes are emitted level-by-level: level 0 has no dependencies, level 1
+/// depends only on level 0, and so on, so clients can parallelize within a
+/// level. Any nodes that never reach in-degree 0 form a cycle and are
+/// returned separately rather than silently dropped.
+pub fn topo_levels(depends_on: &HashMap<String, Vec<String>>) -> TopoResult {
+    let mut in_degree: HashMap<&str, usize> = HashMap::new();
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+
+    for node in depends_on.keys() {
+        in_degree.entry(node).or_insert(0);
+    }
+    for (node, deps) in depends_on {
+        for dep in deps {
+            if !depends_on.contains_key(dep) {
+                continue;
+            }
+            *in_degree.entry(node.as_str()).or_insert(0) += 1;
+            dependents.entry(dep.as_str()).or_default().push(node.as_str());
+        }
+    }
+
+    let mut queue: VecDeque<&str> = in_degree
+        .iter()
+        .filter(|(_, &deg)| deg == 0)
+        .map(|(&n, _)| n)
+        .collect();
+
+    let mut levels = Vec::new();
+    let mut emitted: HashSet<&str> = HashSet::new();
+
+    while !queue.is_empty() {
+        let mut level: Vec<String> = Vec::new();
+        let mut next_queue = VecDeque::new();
+
+        for node in queue.drain(..) {
+            level.push(node.to_string());
+            emitted.insert(node);
+            if let Some(deps) = dependents.get(node) {
+                for &dependent in deps {
+                    let deg = in_degree.get_mut(dependent).unwrap();
+                    *deg -= 1;
+                    if *deg == 0 {
+                        next_queue.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        level.sort();
+        levels.push(level);
+        queue = next_queue;
+    }
+
+    let cycle: Vec<String> = depends_on
+        .keys()
+        .filter(|n| !emitted.contains(n.as_str()))
+        .cloned()
+        .collect();
+
+    TopoResult { levels, cycle }
+}
+
+/// Tarjan's strongly-connected-components algorithm, restricted to `nodes`.
+/// Used to group the leftover nodes from a failed `topo_levels` pass into the
+/// cycles that actually caused them, rather than reporting one big blob.
+/// Only components of size > 1, or a single node with a self-edge, are
+/// returned — an isolated node with no cyclic edge among `nodes` is not a
+/// cycle even if it couldn't be scheduled for some other reason.
+pub fn strongly_connected(
+    depends_on: &HashMap<String, Vec<String>>,
+    nodes: &[String],
+) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        depends_on: &'a HashMap<String, Vec<String>>,
+        allowed: &'a HashSet<&'a str>,
+        index_of: HashMap<&'a str, usize>,
+        lowlink: HashMap<&'a str, usize>,
+        on_stack: HashSet<&'a str>,
+        stack: Vec<&'a str>,
+        next_index: usize,
+        sccs: Vec<Vec<String>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, node: &'a str) {
+            self.index_of.insert(node, self.next_index);
+            self.lowlink.insert(node, self.next_index);
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack.insert(node);
+
+            if let Some(deps) = self.depends_on.get(node) {
+                for dep in deps {
+                    let dep = dep.as_str();
+                    if !self.allowed.contains(dep) {
+                        continue;
+                    }
+                    if !self.index_of.contains_key(dep) {
+                        self.visit(dep);
+                        let dep_low = self.lowlink[dep];
+                        let node_low = self.lowlink[&node];
+                        self.lowlink.insert(node, node_low.min(dep_low));
+                    } else if self.on_stack.contains(dep) {
+                        let dep_idx = self.index_of[dep];
+                        let node_low = self.lowlink[&node];
+                        self.lowlink.insert(node, node_low.min(dep_idx));
+                    }
+                }
+            }
+
+            if self.lowlink[&node] == self.index_of[&node] {
+                let mut component = Vec::new();
+                loop {
+                    let member = self.stack.pop().unwrap();
+                    self.on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == node {
+                        break;
+                    }
+                }
+                let has_self_edge = component.len() == 1
+                    && self
+                        .depends_on
+                        .get(&component[0])
+                        .is_some_and(|deps| deps.iter().any(|d| d == &component[0]));
+                if component.len() > 1 || has_self_edge {
+                    component.sort();
+                    self.sccs.push(component);
+                }
+            }
+        }
+    }
+
+    let allowed: HashSet<&str> = nodes.iter().map(|n| n.as_str()).collect();
+    let mut tarjan = Tarjan {
+        depends_on,
+        allowed: &allowed,
+        index_of: HashMap::new(),
+        lowlink: HashMap::new(),
+        on_stack: HashSet::new(),
+        stack: Vec::new(),
+        next_index: 0,
+        sccs: Vec::new(),
+    };
+
+    let mut sorted_nodes: Vec<&str> = nodes.iter().map(|n| n.as_str()).collect();
+    sorted_nodes.sort();
+    for node in sorted_nodes {
+        if !tarjan.index_of.contains_key(node) {
+            tarjan.visit(node);
+        }
+    }
+
+    tarjan.sccs.sort();
+    tarjan.sccs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(pairs: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(k, deps)| (k.to_string(), deps.iter().map(|d| d.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn levels_a_linear_chain_one_node_per_level() {
+        let depends_on = map(&[("a", &[]), ("b", &["a"]), ("c", &["b"])]);
+        let result = topo_levels(&depends_on);
+        assert!(result.cycle.is_empty());
+        assert_eq!(
+            result.levels,
+            vec![vec!["a".to_string()], vec!["b".to_string()], vec!["c".to_string()]]
+        );
+    }
+
+    #[test]
+    fn groups_independent_nodes_into_the_same_level() {
+        let depends_on = map(&[("a", &[]), ("b", &[]), ("c", &["a", "b"])]);
+        let result = topo_levels(&depends_on);
+        assert!(result.cycle.is_empty());
+        assert_eq!(result.levels[0], vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(result.levels[1], vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn leaves_cyclic_nodes_out_of_levels_and_reports_them() {
+        let depends_on = map(&[("a", &["b"]), ("b", &["a"])]);
+        let result = topo_levels(&depends_on);
+        assert!(result.levels.is_empty());
+        let mut cycle = result.cycle;
+        cycle.sort();
+        assert_eq!(cycle, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn ignores_dependencies_on_unknown_nodes() {
+        // "ghost" isn't a key in depends_on, so it shouldn't block "a" forever.
+        let depends_on = map(&[("a", &["ghost"])]);
+        let result = topo_levels(&depends_on);
+        assert!(result.cycle.is_empty());
+        assert_eq!(result.levels, vec![vec!["a".to_string()]]);
+    }
+
+    #[test]
+    fn finds_a_simple_two_node_cycle() {
+        let depends_on = map(&[("a", &["b"]), ("b", &["a"])]);
+        let nodes = vec!["a".to_string(), "b".to_string()];
+        let sccs = strongly_connected(&depends_on, &nodes);
+        assert_eq!(sccs, vec![vec!["a".to_string(), "b".to_string()]]);
+    }
+
+    #[test]
+    fn excludes_an_isolated_node_with_no_back_edge() {
+        // "c" depends on the a<->b cycle but has no edge back into it. It is
+        // in the node set passed in (as it would be among the leftover nodes
+        // of a failed topo_levels), but strongly_connected must not report it
+        // as a bogus single-node SCC.
+        let depends_on = map(&[("a", &["b"]), ("b", &["a"]), ("c", &["a"])]);
+        let nodes = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let sccs = strongly_connected(&depends_on, &nodes);
+        assert_eq!(sccs, vec![vec!["a".to_string(), "b".to_string()]]);
+    }
+
+    #[test]
+    fn reports_a_self_edge_as_its_own_single_node_cycle() {
+        let depends_on = map(&[("a", &["a"])]);
+        let nodes = vec!["a".to_string()];
+        let sccs = strongly_connected(&depends_on, &nodes);
+        assert_eq!(sccs, vec![vec!["a".to_string()]]);
+    }
+}
@@ -0,0 +1,174 @@
+//! Background job registry for long-running operations (manifest
+//! regeneration, ecosystem-wide sync, etc.) that would otherwise block an
+//! MCP call until completion.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub type JobId = String;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone, Serialize)]
+pub struct JobStatus {
+    pub id: JobId,
+    pub kind: String,
+    pub state: JobState,
+    pub total: usize,
+    pub progress: usize,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+    pub result: Option<serde_json::Value>,
+    #[serde(skip)]
+    pub cancel_flag: Arc<AtomicBool>,
+}
+
+/// Shared handle held by `ToadService`; cheap to clone, safe to hand to a
+/// spawned task so it can report progress (and check for cooperative
+/// cancellation) as it goes.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<JobId, JobStatus>>>,
+}
+
+impl JobRegistry {
+    pub fn register(&self, kind: &str, total: usize) -> (JobId, Arc<AtomicBool>) {
+        let id = unique_id();
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        let status = JobStatus {
+            id: id.clone(),
+            kind: kind.to_string(),
+            state: JobState::Running,
+            total,
+            progress: 0,
+            started_at: now(),
+            finished_at: None,
+            error: None,
+            result: None,
+            cancel_flag: cancel_flag.clone(),
+        };
+        self.jobs.lock().unwrap().insert(id.clone(), status);
+        (id, cancel_flag)
+    }
+
+    pub fn bump_progress(&self, id: &JobId) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.progress += 1;
+        }
+    }
+
+    /// Resizes `total` for a job whose true unit count (e.g. a filtered
+    /// project list) is only known after registration, so progress doesn't
+    /// run past the placeholder total it was registered with.
+    pub fn set_total(&self, id: &JobId, total: usize) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            job.total = total;
+        }
+    }
+
+    pub fn finish(&self, id: &JobId, result: Result<(), String>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            if job.state == JobState::Cancelled {
+                return;
+            }
+            job.finished_at = Some(now());
+            match result {
+                Ok(()) => job.state = JobState::Done,
+                Err(e) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn finish_with_result(&self, id: &JobId, result: Result<serde_json::Value, String>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(id) {
+            if job.state == JobState::Cancelled {
+                return;
+            }
+            job.finished_at = Some(now());
+            match result {
+                Ok(value) => {
+                    job.state = JobState::Done;
+                    job.result = Some(value);
+                }
+                Err(e) => {
+                    job.state = JobState::Failed;
+                    job.error = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.jobs.lock().unwrap().get_mut(id) {
+            Some(job) if matches!(job.state, JobState::Queued | JobState::Running) => {
+                job.cancel_flag.store(true, Ordering::Relaxed);
+                job.state = JobState::Cancelled;
+                job.finished_at = Some(now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobStatus> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<JobStatus> {
+        let mut jobs: Vec<_> = self.jobs.lock().unwrap().values().cloned().collect();
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+}
+
+/// A `toad_core::Reporter` that feeds progress into a `JobRegistry` entry
+/// instead of printing to a terminal, so long operations like
+/// `toad_discovery::sync_registry` report through the same job-status tools
+/// as everything else submitted via `submit_job`.
+pub struct JobReporter {
+    registry: JobRegistry,
+    id: JobId,
+}
+
+impl JobReporter {
+    pub fn new(registry: JobRegistry, id: JobId) -> Self {
+        Self { registry, id }
+    }
+}
+
+impl toad_core::Reporter for JobReporter {
+    fn report(&self, _message: &str) {
+        self.registry.bump_progress(&self.id);
+    }
+}
+
+/// A process-unique, dependency-free id (this crate has no uuid dependency
+/// wired in yet); good enough to key an in-memory map.
+fn unique_id() -> String {
+    use std::sync::atomic::AtomicU64;
+    static COUNTER: AtomicU64 = AtomicU64::new(1);
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("job-{:x}-{:x}", now(), n)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -1,6 +1,18 @@
+mod auth;
+mod bench;
 mod errors;
+mod github;
+mod graph;
+mod jobs;
+mod metrics;
+mod pathtrie;
+mod procrun;
+mod search;
 mod server;
+mod templates;
 mod tools;
+mod trends;
+mod vcs;
 
 use rmcp::ServiceExt;
 use server::ToadService;
@@ -0,0 +1,172 @@
+//! In-process counters and latency/size histograms for MCP tool calls, keyed
+//! by tool name, so operators can see which analytics tools dominate cost
+//! and how often they error without needing an external APM. Exposed via the
+//! `get_metrics` tool as either a JSON snapshot or Prometheus exposition
+//! text.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+const LATENCY_BUCKETS_MS: &[f64] = &[1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0];
+const SIZE_BUCKETS_BYTES: &[f64] = &[256.0, 1024.0, 8192.0, 65536.0, 1_048_576.0];
+
+#[derive(Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            bucket_counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+        for (i, &bound) in self.bounds.iter().enumerate() {
+            if value <= bound {
+                self.bucket_counts[i] += 1;
+            }
+        }
+        *self.bucket_counts.last_mut().unwrap() += 1;
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let buckets: Vec<_> = self
+            .bounds
+            .iter()
+            .map(|b| b.to_string())
+            .chain(std::iter::once("+Inf".to_string()))
+            .zip(&self.bucket_counts)
+            .map(|(le, count)| serde_json::json!({"le": le, "count": count}))
+            .collect();
+        serde_json::json!({
+            "sum": self.sum,
+            "count": self.count,
+            "buckets": buckets,
+        })
+    }
+}
+
+#[derive(Clone)]
+struct ToolMetrics {
+    invocations: u64,
+    errors: u64,
+    blocking_latency_ms: Histogram,
+    total_latency_ms: Histogram,
+    result_bytes: Histogram,
+}
+
+impl ToolMetrics {
+    fn new() -> Self {
+        Self {
+            invocations: 0,
+            errors: 0,
+            blocking_latency_ms: Histogram::new(LATENCY_BUCKETS_MS),
+            total_latency_ms: Histogram::new(LATENCY_BUCKETS_MS),
+            result_bytes: Histogram::new(SIZE_BUCKETS_BYTES),
+        }
+    }
+}
+
+/// Shared handle held by `ToadService`; cheap to clone, safe to read from
+/// `get_metrics` while other tool calls are recording into it concurrently.
+#[derive(Clone, Default)]
+pub struct MetricsRegistry(Arc<Mutex<HashMap<String, ToolMetrics>>>);
+
+impl MetricsRegistry {
+    /// Records one completed call: `blocking_ms` is time spent inside the
+    /// handler's `spawn_blocking` body, `total_ms` is the wall-clock time for
+    /// the whole async call (including thread-pool scheduling delay).
+    pub fn record(&self, tool: &str, blocking_ms: f64, total_ms: f64, result_bytes: usize, is_err: bool) {
+        let mut map = self.0.lock().unwrap();
+        let entry = map.entry(tool.to_string()).or_insert_with(ToolMetrics::new);
+        entry.invocations += 1;
+        if is_err {
+            entry.errors += 1;
+        }
+        entry.blocking_latency_ms.observe(blocking_ms);
+        entry.total_latency_ms.observe(total_ms);
+        entry.result_bytes.observe(result_bytes as f64);
+    }
+
+    pub fn snapshot_json(&self) -> serde_json::Value {
+        let map = self.0.lock().unwrap();
+        let tools: serde_json::Map<String, serde_json::Value> = map
+            .iter()
+            .map(|(tool, m)| {
+                (
+                    tool.clone(),
+                    serde_json::json!({
+                        "invocations": m.invocations,
+                        "errors": m.errors,
+                        "blocking_latency_ms": m.blocking_latency_ms.to_json(),
+                        "total_latency_ms": m.total_latency_ms.to_json(),
+                        "result_bytes": m.result_bytes.to_json(),
+                    }),
+                )
+            })
+            .collect();
+        serde_json::Value::Object(tools)
+    }
+
+    /// Renders the same data as Prometheus text exposition format, for
+    /// operators scraping this process directly rather than polling the
+    /// `get_metrics` tool for JSON.
+    pub fn snapshot_prometheus(&self) -> String {
+        let map = self.0.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# TYPE toad_mcp_tool_invocations_total counter\n");
+        for (tool, m) in map.iter() {
+            out.push_str(&format!(
+                "toad_mcp_tool_invocations_total{{tool=\"{}\"}} {}\n",
+                tool, m.invocations
+            ));
+        }
+        out.push_str("# TYPE toad_mcp_tool_errors_total counter\n");
+        for (tool, m) in map.iter() {
+            out.push_str(&format!(
+                "toad_mcp_tool_errors_total{{tool=\"{}\"}} {}\n",
+                tool, m.errors
+            ));
+        }
+        for (name, extract) in [
+            (
+                "toad_mcp_tool_blocking_latency_ms",
+                (|m: &ToolMetrics| &m.blocking_latency_ms) as fn(&ToolMetrics) -> &Histogram,
+            ),
+            ("toad_mcp_tool_total_latency_ms", |m| &m.total_latency_ms),
+            ("toad_mcp_tool_result_bytes", |m| &m.result_bytes),
+        ] {
+            out.push_str(&format!("# TYPE {} histogram\n", name));
+            for (tool, m) in map.iter() {
+                let hist = extract(m);
+                for (le, count) in hist
+                    .bounds
+                    .iter()
+                    .map(|b| b.to_string())
+                    .chain(std::iter::once("+Inf".to_string()))
+                    .zip(&hist.bucket_counts)
+                {
+                    out.push_str(&format!(
+                        "{}_bucket{{tool=\"{}\",le=\"{}\"}} {}\n",
+                        name, tool, le, count
+                    ));
+                }
+                out.push_str(&format!("{}_sum{{tool=\"{}\"}} {}\n", name, tool, hist.sum));
+                out.push_str(&format!("{}_count{{tool=\"{}\"}} {}\n", name, tool, hist.count));
+            }
+        }
+
+        out
+    }
+}
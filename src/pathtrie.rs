@@ -0,0 +1,89 @@
+//! Prefix trie over project paths, used to attribute a changed file to the
+//! deepest (most specific) registered project that contains it.
+
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+#[derive(Default)]
+pub struct PathTrie {
+    root: Node,
+}
+
+#[derive(Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    project: Option<String>,
+}
+
+impl PathTrie {
+    pub fn build<'a>(projects: impl Iterator<Item = (&'a str, &'a Path)>) -> Self {
+        let mut trie = PathTrie::default();
+        for (name, path) in projects {
+            let mut node = &mut trie.root;
+            for component in path.components() {
+                if let Component::Normal(part) = component {
+                    node = node
+                        .children
+                        .entry(part.to_string_lossy().to_string())
+                        .or_default();
+                }
+            }
+            node.project = Some(name.to_string());
+        }
+        trie
+    }
+
+    /// Walks `path` component by component, returning the name of the
+    /// deepest project whose registered path is a prefix of it.
+    pub fn lookup(&self, path: &Path) -> Option<&str> {
+        let mut node = &self.root;
+        let mut best = node.project.as_deref();
+
+        for component in path.components() {
+            if let Component::Normal(part) = component {
+                match node.children.get(&part.to_string_lossy().to_string()) {
+                    Some(next) => {
+                        node = next;
+                        if node.project.is_some() {
+                            best = node.project.as_deref();
+                        }
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn attributes_a_file_to_its_containing_project() {
+        let projects = [("core", PathBuf::from("libs/core"))];
+        let trie = PathTrie::build(projects.iter().map(|(n, p)| (*n, p.as_path())));
+        assert_eq!(trie.lookup(Path::new("libs/core/src/lib.rs")), Some("core"));
+    }
+
+    #[test]
+    fn prefers_the_deepest_matching_project_for_nested_registrations() {
+        let projects = [
+            ("hub", PathBuf::from("libs")),
+            ("core", PathBuf::from("libs/core")),
+        ];
+        let trie = PathTrie::build(projects.iter().map(|(n, p)| (*n, p.as_path())));
+        assert_eq!(trie.lookup(Path::new("libs/core/src/lib.rs")), Some("core"));
+        assert_eq!(trie.lookup(Path::new("libs/other/file.rs")), Some("hub"));
+    }
+
+    #[test]
+    fn returns_none_for_a_path_outside_any_registered_project() {
+        let projects = [("core", PathBuf::from("libs/core"))];
+        let trie = PathTrie::build(projects.iter().map(|(n, p)| (*n, p.as_path())));
+        assert_eq!(trie.lookup(Path::new("docs/readme.md")), None);
+    }
+}
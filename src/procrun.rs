@@ -0,0 +1,105 @@
+//! Shared helper for running a child process with a wall-clock timeout and
+//! per-stream output truncation, used by the various "run a command across
+//! projects" tools so each one doesn't reimplement child-process plumbing.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_STREAM_LIMIT: usize = 64 * 1024;
+
+pub struct RunOutcome {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stdout_truncated: bool,
+    pub stderr: String,
+    pub stderr_truncated: bool,
+    pub duration_ms: u128,
+    pub timed_out: bool,
+}
+
+/// Runs `argv` (argv[0] is the program) in `cwd`, killing it if it runs
+/// longer than `timeout`. Output is captured up to `stream_limit` bytes per
+/// stream; anything beyond that is dropped rather than blowing up the
+/// response size.
+pub fn run(
+    argv: &[String],
+    cwd: &Path,
+    timeout: Duration,
+    stream_limit: usize,
+) -> std::io::Result<RunOutcome> {
+    let (program, args) = argv.split_first().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "empty command")
+    })?;
+
+    let start = Instant::now();
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain stdout/stderr on their own threads as soon as the child is
+    // spawned. If we waited until after the child exits (or is killed) to
+    // read these pipes, a process that writes more than the OS pipe buffer
+    // (~64KB on Linux) would block on `write()` and never exit, making every
+    // run falsely time out.
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+    let stdout_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut out) = stdout_pipe {
+            let _ = out.read_to_end(&mut buf);
+        }
+        buf
+    });
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut err) = stderr_pipe {
+            let _ = err.read_to_end(&mut buf);
+        }
+        buf
+    });
+
+    let mut timed_out = false;
+    loop {
+        match child.try_wait()? {
+            Some(_status) => break,
+            None => {
+                if start.elapsed() >= timeout {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    timed_out = true;
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(25));
+            }
+        }
+    }
+
+    let mut stdout_buf = stdout_reader.join().unwrap_or_default();
+    let mut stderr_buf = stderr_reader.join().unwrap_or_default();
+
+    let stdout_truncated = stdout_buf.len() > stream_limit;
+    let stderr_truncated = stderr_buf.len() > stream_limit;
+    stdout_buf.truncate(stream_limit);
+    stderr_buf.truncate(stream_limit);
+
+    let exit_code = if timed_out {
+        None
+    } else {
+        child.wait().ok().and_then(|s| s.code())
+    };
+
+    Ok(RunOutcome {
+        exit_code,
+        stdout: String::from_utf8_lossy(&stdout_buf).to_string(),
+        stdout_truncated,
+        stderr: String::from_utf8_lossy(&stderr_buf).to_string(),
+        stderr_truncated,
+        duration_ms: start.elapsed().as_millis(),
+        timed_out,
+    })
+}
@@ -0,0 +1,206 @@
+//! Scoring-based, typo-tolerant project search.
+//!
+//! Replaces naive `contains()` filtering with a weighted, fuzzy-matched
+//! ranking: each query token is matched against a set of weighted fields,
+//! and projects are returned sorted by descending score.
+
+use toad_core::Project;
+
+/// Bounded-edit-distance tolerance ladder, Meilisearch-style: short tokens
+/// require an exact, prefix or substring match, longer tokens tolerate more typos.
+fn max_edits(token_len: usize) -> usize {
+    match token_len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1)
+                .min(curr[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Scores a single query token against a single field value. Returns `None`
+/// if the token doesn't match within its edit-distance tolerance.
+fn score_token_against_field(token: &str, field: &str) -> Option<f64> {
+    let field = field.to_lowercase();
+    if field == token {
+        return Some(1.0);
+    }
+    if field.starts_with(token) {
+        return Some(0.9);
+    }
+    if field.contains(token) {
+        return Some(1.0);
+    }
+
+    let tolerance = max_edits(token.len());
+    if tolerance == 0 {
+        return None;
+    }
+    field
+        .split_whitespace()
+        .filter_map(|word| {
+            let edits = levenshtein(token, word);
+            if edits <= tolerance {
+                Some(0.8 * (1.0 - edits as f64 / token.len().max(1) as f64))
+            } else {
+                None
+            }
+        })
+        .fold(None, |best: Option<f64>, score| match best {
+            Some(b) if b >= score => Some(b),
+            _ => Some(score),
+        })
+}
+
+struct WeightedField<'a> {
+    weight: f64,
+    values: Vec<&'a str>,
+}
+
+fn fields_for(project: &Project) -> Vec<WeightedField<'_>> {
+    vec![
+        WeightedField {
+            weight: 3.0,
+            values: vec![project.name.as_str()],
+        },
+        WeightedField {
+            weight: 2.0,
+            values: project.tags.iter().map(|t| t.as_str()).collect(),
+        },
+        WeightedField {
+            weight: 1.5,
+            values: project
+                .dna
+                .roles
+                .iter()
+                .chain(project.dna.capabilities.iter())
+                .map(|s| s.as_str())
+                .collect(),
+        },
+        WeightedField {
+            weight: 1.2,
+            values: vec![project.stack.as_str()],
+        },
+        WeightedField {
+            weight: 1.0,
+            values: project
+                .dna
+                .structural_patterns
+                .iter()
+                .map(|s| s.as_str())
+                .collect(),
+        },
+    ]
+}
+
+/// Scores `project` against `query`, returning `None` if no token matched
+/// any field at all (the project should then be excluded from results).
+pub fn score(project: &Project, query: &str) -> Option<f64> {
+    let tokens: Vec<String> = query
+        .to_lowercase()
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect();
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let fields = fields_for(project);
+    let mut total = 0.0;
+    let mut any_match = false;
+
+    for token in &tokens {
+        let mut best_for_token = 0.0;
+        for field in &fields {
+            for value in &field.values {
+                if let Some(s) = score_token_against_field(token, value) {
+                    let weighted = s * field.weight;
+                    if weighted > best_for_token {
+                        best_for_token = weighted;
+                    }
+                }
+            }
+        }
+        if best_for_token > 0.0 {
+            any_match = true;
+        }
+        total += best_for_token;
+    }
+
+    any_match.then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, tags: &[&str]) -> Project {
+        Project {
+            name: name.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Project::default()
+        }
+    }
+
+    #[test]
+    fn levenshtein_counts_single_edits() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("toad", "toad"), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+
+    #[test]
+    fn exact_and_contains_matches_outrank_prefix() {
+        let exact = score_token_against_field("api", "api").unwrap();
+        let prefix = score_token_against_field("api", "apiserver").unwrap();
+        let contains = score_token_against_field("api", "my-api-gateway").unwrap();
+        assert_eq!(exact, 1.0);
+        assert_eq!(prefix, 0.9);
+        assert_eq!(contains, 1.0);
+        assert!(prefix < exact);
+    }
+
+    #[test]
+    fn short_tokens_get_no_fuzzy_tolerance() {
+        // "api" is <= 3 chars, so max_edits is 0: a one-off typo must not match.
+        assert_eq!(score_token_against_field("api", "apx server"), None);
+    }
+
+    #[test]
+    fn longer_tokens_tolerate_a_typo() {
+        // "server" (6 chars) tolerates 1 edit; "servar" is one substitution away.
+        assert!(score_token_against_field("server", "servar").is_some());
+    }
+
+    #[test]
+    fn score_is_none_when_no_token_matches_any_field() {
+        let p = project("toad-mcp", &["#rust"]);
+        assert_eq!(score(&p, "zzzzzz-nonexistent"), None);
+    }
+
+    #[test]
+    fn score_prefers_name_match_over_tag_match() {
+        let by_name = project("gateway", &[]);
+        let by_tag = project("unrelated", &["#gateway"]);
+        let name_score = score(&by_name, "gateway").unwrap();
+        let tag_score = score(&by_tag, "gateway").unwrap();
+        assert!(name_score > tag_score);
+    }
+}
@@ -14,6 +14,10 @@ use toad_core::{GlobalConfig, Workspace};
 #[derive(Clone)]
 pub struct ToadService {
     pub tool_router: ToolRouter<Self>,
+    pub jobs: crate::jobs::JobRegistry,
+    pub metrics: crate::metrics::MetricsRegistry,
+    pub trends: crate::trends::TrendCache,
+    pub auth: crate::auth::AuthGate,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -126,6 +130,36 @@ pub struct BranchesParams {
 pub struct ManifestParams {
     /// Optional project name for project-specific context
     pub project: Option<String>,
+    /// Bypass the per-project fingerprint cache and rewrite every CONTEXT.md
+    pub force: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct StartJobParams {
+    /// Operation to run in the background: "project_stats", "generate_manifest", or "sync_registry"
+    pub kind: String,
+    /// Filter by project name (substring), used by "project_stats" and "generate_manifest"
+    pub query: Option<String>,
+    /// Filter by tag, used by "project_stats"
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CancelJobParams {
+    /// Job id to cancel
+    pub job_id: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetBudgetReportParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetJobStatusParams {
+    /// Job id returned by an async tool such as generate_manifest_async
+    pub job_id: String,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -136,6 +170,273 @@ pub struct RegisterContextParams {
     pub path: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct ImportGithubOrgParams {
+    /// GitHub organization or user name
+    pub org: String,
+    /// Personal access token (falls back to GITHUB_TOKEN env var)
+    pub token: Option<String>,
+    /// Context to register the imported projects into (defaults to active context)
+    pub context: Option<String>,
+    /// Clone each repo under the context path instead of recording it remote-only
+    pub clone: Option<bool>,
+    /// Only import repos carrying this GitHub topic
+    pub topic: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RankProjectsParams {
+    /// Search term (tokenized and matched per-token, with typo tolerance)
+    pub query: String,
+    /// Narrow results by tag
+    pub tag: Option<String>,
+    /// Maximum number of results to return
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SyncProjectsParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Report what would happen without mutating anything
+    pub dry_run: Option<bool>,
+    /// Fast-forward the working branch after fetching
+    pub pull: Option<bool>,
+    /// Remote name override. Not applied: fetch and pull go through
+    /// `toad_git::fetch`/`toad_git::pull` with their default remote
+    pub remote: Option<String>,
+    /// Also update submodules after the main fetch/pull
+    pub recurse_submodules: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SpawnCommandParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Shell command string to run in each matching project's directory
+    pub command: String,
+    /// Maximum concurrent commands (default: number of CPUs)
+    pub concurrency: Option<usize>,
+    /// Per-project timeout in seconds before the child is killed
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SpawnInProjectsParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Command argv (first element is the program, avoids shell injection)
+    pub command: Vec<String>,
+    /// Abort remaining projects as soon as one command fails
+    pub fail_fast: Option<bool>,
+    /// Maximum concurrent commands (default: number of CPUs)
+    pub parallelism: Option<usize>,
+    /// Per-command timeout in seconds before the child is killed
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RunGitActionParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Action to apply: "fetch", "pull", "checkout", or "switch"
+    pub action: String,
+    /// Target branch for "checkout"/"switch"
+    pub branch: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SyncWorkspaceParams {
+    /// GitHub org/user to treat as the remote source of truth
+    pub org: Option<String>,
+    /// Auth token (falls back to GITHUB_TOKEN env var)
+    pub token: Option<String>,
+    /// Actually perform the clones instead of just reporting the plan
+    pub apply: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetAffectedProjectsParams {
+    /// Base ref (defaults to HEAD~1)
+    pub base: Option<String>,
+    /// Head ref (defaults to HEAD)
+    pub head: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RunAcrossProjectsParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag (e.g., #rust)
+    pub tag: Option<String>,
+    /// Shell command to run in each matching project's directory
+    pub command: String,
+    /// Run the command in every project concurrently instead of sequentially
+    pub parallel: Option<bool>,
+    /// Abort remaining projects as soon as one command fails
+    pub fail_fast: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CloneMissingParams {
+    /// Context to populate (defaults to the active context)
+    pub context: Option<String>,
+    /// GitHub org/user whose repos should be cloned in
+    pub org: Option<String>,
+    /// Explicit list of clone URLs to use instead of an org listing
+    pub manifest: Option<Vec<String>>,
+    /// Auth token (falls back to GITHUB_TOKEN env var), only used with `org`
+    pub token: Option<String>,
+    /// Clone protocol when listing an org: "https" (default) or "ssh"
+    pub protocol: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GitSyncParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Push the current branch when it is ahead of its upstream and not behind it
+    /// (a branch that needed a fast-forward pull has nothing to push)
+    pub push: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RunTaskParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Shell command string to run in each matching project's directory
+    pub command: String,
+    /// Maximum concurrent commands (default: number of CPUs)
+    pub concurrency: Option<usize>,
+    /// Per-project timeout in seconds before the child is killed
+    pub timeout_secs: Option<u64>,
+    /// Ignore the cache and re-run every project regardless of hash
+    pub force: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ResolveBuildOrderParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ResolveMigrationOrderParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RunWorkloadParams {
+    /// Path to a JSON workload file: `{ "name": str, "steps": [{ "tool": str, "args": {..}, "repeat": int }], "baseline": optional path }`.
+    /// Each step's `tool` is one of "analyze_velocity", "analyze_debt", "analyze_health", "analyze_deps", or "get_project_stats"; `args` supports `query`, `tag`, and `days` the same way the equivalent standalone tool does.
+    pub workload_path: String,
+    /// Percentage median-latency increase over the baseline before a step is flagged as regressed (default: 20.0)
+    pub regression_threshold_pct: Option<f64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AnalyzeVelocityParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Commit history window in days (default: 30)
+    pub days: Option<u64>,
+    /// Maximum concurrent per-project analyses (default: number of CPUs, or `TOAD_ANALYTICS_CONCURRENCY`)
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AnalyzeDebtParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Maximum concurrent per-project analyses (default: number of CPUs, or `TOAD_ANALYTICS_CONCURRENCY`)
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AnalyzeHealthParams {
+    /// Filter by project name (substring)
+    pub query: Option<String>,
+    /// Filter by tag
+    pub tag: Option<String>,
+    /// Maximum concurrent per-project analyses (default: number of CPUs, or `TOAD_ANALYTICS_CONCURRENCY`)
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetMetricsParams {
+    /// Output encoding: "json" (default) or "prometheus"
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct BatchAnalyzeParams {
+    /// Sub-requests to run against a single shared registry load. Each `tool` is one of
+    /// "analyze_velocity", "analyze_debt", "analyze_health", "analyze_deps", or "get_project_stats";
+    /// `args` supports `query`, `tag`, and `days` the same way the equivalent standalone tool does.
+    pub requests: Vec<BatchAnalyzeRequest>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct BatchAnalyzeRequest {
+    /// Caller-chosen key used to locate this sub-request's result (or error) in the response object
+    pub id: String,
+    pub tool: String,
+    #[serde(default)]
+    pub args: serde_json::Value,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AuthenticateParams {
+    /// Shared secret configured via TOAD_MCP_SECRET or TOAD_MCP_SECRET_FILE
+    pub token: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct AnalyzeTrendsParams {
+    /// Commit/activity history window in days (default: 90)
+    pub days: Option<u64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct WatchTrendsParams {
+    /// Commit/activity history window in days (default: 90), same as analyze_trends
+    pub days: Option<u64>,
+    /// Digest returned by a previous analyze_trends/watch_trends call; if the recomputed report
+    /// matches this digest, the call blocks (instead of returning the unchanged report immediately)
+    pub since_digest: Option<String>,
+    /// Maximum seconds to block waiting for a change before returning the unchanged marker (default: 30, max: 120)
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SyncRegistryParams {
+    /// Additional sources to merge in: other registered context names, or
+    /// `https://` URLs to a remote ATLAS.json. Deduplicated by project name;
+    /// on a collision the copy with the newer HEAD commit wins.
+    pub sources: Option<Vec<String>>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct TagParams {
     /// Project name (optional if using filters)
@@ -157,6 +458,10 @@ impl ToadService {
         let _ = Workspace::discover()?;
         Ok(Self {
             tool_router: Self::tool_router(),
+            jobs: crate::jobs::JobRegistry::default(),
+            metrics: crate::metrics::MetricsRegistry::default(),
+            trends: crate::trends::TrendCache::default(),
+            auth: crate::auth::AuthGate::from_env()?,
         })
     }
 
@@ -167,6 +472,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<ListProjectsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let params = params.0;
 
         let q_filter: Option<String> = params.query.map(|s| s.to_lowercase());
@@ -234,6 +541,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetProjectDetailParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let name = params.0.name;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -274,6 +583,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetProjectDetailParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let name = params.0.name;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -304,34 +615,49 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<CompareProjectsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let source = params.0.source;
         let target = params.0.target;
 
-        let result = tokio::task::spawn_blocking(move || {
-            let ws = Workspace::discover()?;
-            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
-
-            let proj_a = registry
-                .projects
-                .iter()
-                .find(|p| p.name == source)
-                .ok_or_else(|| {
-                    toad_core::ToadError::Other(format!("Source project '{}' not found", source))
-                })?;
-            let proj_b = registry
-                .projects
-                .iter()
-                .find(|p| p.name == target)
-                .ok_or_else(|| {
-                    toad_core::ToadError::Other(format!("Target project '{}' not found", target))
-                })?;
-
-            let preflight = toad_ops::migration::compare_projects(proj_a, proj_b);
-            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&preflight)?)
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+                let proj_a = registry
+                    .projects
+                    .iter()
+                    .find(|p| p.name == source)
+                    .ok_or_else(|| {
+                        toad_core::ToadError::Other(format!("Source project '{}' not found", source))
+                    })?;
+                let proj_b = registry
+                    .projects
+                    .iter()
+                    .find(|p| p.name == target)
+                    .ok_or_else(|| {
+                        toad_core::ToadError::Other(format!("Target project '{}' not found", target))
+                    })?;
+
+                let preflight = toad_ops::migration::compare_projects(proj_a, proj_b);
+                Ok(serde_json::to_string_pretty(&preflight)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
         })
         .await
-        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
-        .map_err(crate::errors::toad_error_to_mcp)?;
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "compare_projects",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
@@ -343,6 +669,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<SearchProjectsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query.to_lowercase();
 
         let result = tokio::task::spawn_blocking(move || {
@@ -384,6 +712,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<SearchProjectsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
@@ -399,6 +729,55 @@ impl ToadService {
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
+    #[tool(
+        description = "[Discovery] Relevance-ranked, typo-tolerant project search. Tokenizes the query and scores weighted fields (name > tags > dna > stack > structural_patterns) with bounded Levenshtein fuzzy matching, returning results sorted by descending score."
+    )]
+    pub async fn rank_projects(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<RankProjectsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let mut scored: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    params.tag.as_ref().is_none_or(|t| {
+                        p.tags.iter().any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    })
+                })
+                .filter_map(|p| crate::search::score(&p, &params.query).map(|score| (p, score)))
+                .collect();
+
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+            if let Some(limit) = params.limit {
+                scored.truncate(limit);
+            }
+
+            let results: Vec<_> = scored
+                .into_iter()
+                .map(|(p, score)| {
+                    let mut value = serde_json::to_value(&p).unwrap_or_default();
+                    value["score"] = serde_json::json!(score);
+                    value
+                })
+                .collect();
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&results)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
     #[tool(
         description = "[Discovery] Get high-level ecosystem summary (SYSTEM_PROMPT.md format). Token-limited overview of all projects."
     )]
@@ -406,6 +785,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetEcosystemSummaryParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let token_limit = params.0.token_limit;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -432,6 +813,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetEcosystemStatusParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
@@ -453,23 +836,38 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetProjectStatsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
-        let result = tokio::task::spawn_blocking(move || {
-            let ws = Workspace::discover()?;
-            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
-
-            let report = toad_ops::stats::generate_analytics_report(
-                &registry.projects,
-                query.as_deref(),
-                tag.as_deref(),
-            );
-            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&report)?)
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+                let report = toad_ops::stats::generate_analytics_report(
+                    &registry.projects,
+                    query.as_deref(),
+                    tag.as_deref(),
+                );
+                Ok(serde_json::to_string_pretty(&report)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
         })
         .await
-        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
-        .map_err(crate::errors::toad_error_to_mcp)?;
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "get_project_stats",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
@@ -479,6 +877,8 @@ impl ToadService {
         &self,
         _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let result = tokio::task::spawn_blocking(move || {
             let config = GlobalConfig::load(None)?.unwrap_or_default();
             let active = config
@@ -506,6 +906,8 @@ impl ToadService {
         &self,
         _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let result = tokio::task::spawn_blocking(move || {
             let config = GlobalConfig::load(None)?.unwrap_or_default();
             let active = config
@@ -544,6 +946,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<SwitchContextParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let name = params.0.name;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -570,6 +974,8 @@ impl ToadService {
         &self,
         _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let result = tokio::task::spawn_blocking(move || {
             let ws = Workspace::discover()?;
             let atlas_path = ws.atlas_path();
@@ -595,6 +1001,8 @@ impl ToadService {
         &self,
         _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let result = tokio::task::spawn_blocking(move || {
             let ws = Workspace::discover()?;
             let manifest_path = ws.manifest_path();
@@ -622,6 +1030,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<GetProjectDetailParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let name = params.0.name;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -653,6 +1063,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<RevealParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
@@ -669,12 +1081,14 @@ impl ToadService {
     }
 
     #[tool(
-        description = "[Discovery] Get Git status across all projects. Shows uncommitted changes, unpushed commits, and branch info."
+        description = "[Discovery] Get VCS status across all projects (Git or Mercurial, detected per project). Shows uncommitted changes, unpushed commits, and branch info; projects with no recognized VCS are reported rather than erroring out the call."
     )]
     pub async fn get_git_status(
         &self,
         params: rmcp::handler::server::wrapper::Parameters<StatusParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
@@ -709,8 +1123,23 @@ impl ToadService {
                 );
             }
 
-            let report = toad_git::generate_multi_repo_status(&targets)?;
-            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&report)?)
+            let mut reports = Vec::with_capacity(targets.len());
+            for p in &targets {
+                match crate::vcs::status(p) {
+                    Ok(status) => reports.push(serde_json::json!({
+                        "project": p.name,
+                        "vcs": status.vcs.as_str(),
+                        "status": status.detail,
+                    })),
+                    Err(e) => reports.push(serde_json::json!({
+                        "project": p.name,
+                        "vcs": crate::vcs::detect(&p.path).as_str(),
+                        "error": e.to_string(),
+                    })),
+                }
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
         })
         .await
         .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
@@ -726,34 +1155,51 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<StatsParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
 
-        let result = tokio::task::spawn_blocking(move || {
-            let ws = Workspace::discover()?;
-            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
-
-            let report = toad_ops::stats::generate_analytics_report(
-                &registry.projects,
-                query.as_deref(),
-                tag.as_deref(),
-            );
-            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&report)?)
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+                let report = toad_ops::stats::generate_analytics_report(
+                    &registry.projects,
+                    query.as_deref(),
+                    tag.as_deref(),
+                );
+                Ok(serde_json::to_string_pretty(&report)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
         })
         .await
-        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
-        .map_err(crate::errors::toad_error_to_mcp)?;
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "get_disk_stats",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
     #[tool(
-        description = "[Discovery] List all branches across projects. Shows current branch and available local/remote branches."
+        description = "[Discovery] List all branches across projects (Git or Mercurial, detected per project). Shows available local/remote branches."
     )]
     pub async fn list_branches(
         &self,
         params: rmcp::handler::server::wrapper::Parameters<BranchesParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let query = params.0.query;
         let tag = params.0.tag;
         let all = params.0.all.unwrap_or(false);
@@ -791,17 +1237,18 @@ impl ToadService {
 
             let mut output = Vec::new();
             for p in targets {
-                let local = toad_git::branches::list_local_branches(&p.path)?;
-                let mut branches = local;
-                if all {
-                    let remote = toad_git::branches::list_remote_branches(&p.path)?;
-                    branches.extend(remote);
+                match crate::vcs::list_branches(&p.path, all) {
+                    Ok((vcs, branches)) => output.push(serde_json::json!({
+                        "project": p.name,
+                        "vcs": vcs.as_str(),
+                        "branches": branches,
+                    })),
+                    Err(e) => output.push(serde_json::json!({
+                        "project": p.name,
+                        "vcs": crate::vcs::detect(&p.path).as_str(),
+                        "error": e.to_string(),
+                    })),
                 }
-
-                output.push(serde_json::json!({
-                    "project": p.name,
-                    "branches": branches,
-                }));
             }
 
             Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&output)?)
@@ -818,16 +1265,81 @@ impl ToadService {
     )]
     pub async fn sync_registry(
         &self,
-        _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
+        params: rmcp::handler::server::wrapper::Parameters<SyncRegistryParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let sources = params.0.sources.unwrap_or_default();
+
         let result = tokio::task::spawn_blocking(move || {
             let ws = Workspace::discover()?;
             let reporter = toad_core::NoOpReporter;
-            let count = toad_discovery::sync_registry(&ws, &reporter)?;
-            Ok::<_, toad_core::ToadError>(format!(
-                "Registry synchronized ({} projects found)",
-                count
-            ))
+            let local_count = toad_discovery::sync_registry(&ws, &reporter)?;
+
+            if sources.is_empty() {
+                return Ok::<_, toad_core::ToadError>(format!(
+                    "Registry synchronized ({} projects found)",
+                    local_count
+                ));
+            }
+
+            let mut merged = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+            let mut by_name: std::collections::HashMap<String, usize> = merged
+                .projects
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.name.clone(), i))
+                .collect();
+            let mut from_source: std::collections::HashMap<String, usize> =
+                std::collections::HashMap::new();
+            from_source.insert("local".to_string(), merged.projects.len());
+            let mut collisions = Vec::new();
+
+            for source in &sources {
+                let incoming: Vec<toad_core::Project> = if source.starts_with("http") {
+                    let body = ureq::get(source)
+                        .call()
+                        .map_err(|e| {
+                            toad_core::ToadError::Other(format!(
+                                "Failed to fetch remote ATLAS '{}': {}",
+                                source, e
+                            ))
+                        })?
+                        .into_string()
+                        .map_err(|e| toad_core::ToadError::Other(e.to_string()))?;
+                    serde_json::from_str(&body)
+                        .map_err(|e| toad_core::ToadError::Other(format!("Invalid ATLAS.json from '{}': {}", source, e)))?
+                } else {
+                    toad_core::ProjectRegistry::load(Some(source.as_str()), None)?.projects
+                };
+
+                from_source.insert(source.clone(), incoming.len());
+
+                for project in incoming {
+                    if let Some(&idx) = by_name.get(&project.name) {
+                        collisions.push(project.name.clone());
+                        let incoming_epoch = project_commit_epoch(&project.path);
+                        let existing_epoch = project_commit_epoch(&merged.projects[idx].path);
+                        if incoming_epoch > existing_epoch {
+                            merged.projects[idx] = project;
+                        }
+                    } else {
+                        by_name.insert(project.name.clone(), merged.projects.len());
+                        merged.projects.push(project);
+                    }
+                }
+            }
+
+            merged.save(&ws.registry_path(ws.active_context.as_deref()))?;
+
+            Ok::<_, toad_core::ToadError>(
+                serde_json::json!({
+                    "total_projects": merged.projects.len(),
+                    "from_source": from_source,
+                    "collisions": collisions,
+                })
+                .to_string(),
+            )
         })
         .await
         .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
@@ -837,13 +1349,16 @@ impl ToadService {
     }
 
     #[tool(
-        description = "[Context] Generate AI context files (MANIFEST.md, ATLAS.json, SYSTEM_PROMPT.md, CONTEXT.md). Refreshes AI intuition."
+        description = "[Context] Generate AI context files (MANIFEST.md, ATLAS.json, SYSTEM_PROMPT.md, CONTEXT.md). Refreshes AI intuition. Per-project CONTEXT.md files are skipped when their fingerprint (path, stack, tags, submodules, HEAD commit) hasn't changed since the last run; pass force:true to rewrite everything."
     )]
     pub async fn generate_manifest(
         &self,
         params: rmcp::handler::server::wrapper::Parameters<ManifestParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let project_filter = params.0.project;
+        let force = params.0.force.unwrap_or(false);
 
         let result = tokio::task::spawn_blocking(move || {
             let ws = Workspace::discover()?;
@@ -897,28 +1412,415 @@ impl ToadService {
             let llms_txt = toad_manifest::generate_llms_txt(&projects);
             fs::write(ws.shadows_dir.join("llms.txt"), llms_txt)?;
 
-            // Per-project
-            for p in &projects {
+            // Per-project, skipping any whose fingerprint hasn't changed since the last run
+            let template_path = crate::templates::template_path(&ws.shadows_dir);
+            let template = fs::read_to_string(&template_path).ok();
+
+            let fingerprints_path = ws.shadows_dir.join("fingerprints.json");
+            let mut fingerprints: std::collections::HashMap<String, String> =
+                fs::read_to_string(&fingerprints_path)
+                    .ok()
+                    .and_then(|s| serde_json::from_str(&s).ok())
+                    .unwrap_or_default();
+
+            let mut regenerated = Vec::new();
+            let mut unchanged = Vec::new();
+
+            for p in &projects {
+                let fp = project_fingerprint(p);
+                if !force && fingerprints.get(&p.name) == Some(&fp) {
+                    unchanged.push(p.name.clone());
+                    continue;
+                }
+
                 let proj_shadow_dir = ws.shadows_dir.join(&p.name);
                 fs::create_dir_all(&proj_shadow_dir)?;
 
+                let context_md = match &template {
+                    Some(tmpl) => crate::templates::render(tmpl, p)?,
+                    None => toad_manifest::generate_project_context_md(
+                        p,
+                        Some(config.budget.project_tokens),
+                    ),
+                };
+                fs::write(proj_shadow_dir.join("CONTEXT.md"), context_md)?;
+
+                fingerprints.insert(p.name.clone(), fp);
+                regenerated.push(p.name.clone());
+            }
+
+            fs::write(&fingerprints_path, serde_json::to_string_pretty(&fingerprints)?)?;
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&serde_json::json!({
+                "projects_total": projects.len(),
+                "regenerated": regenerated,
+                "unchanged": unchanged,
+            }))?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Context] Regenerate MANIFEST.md, SYSTEM_PROMPT.md, llms.txt and every matching project's CONTEXT.md in the background and return a job id immediately. Poll with get_job_status instead of blocking on a large registry. Every CONTEXT.md is rewritten with the built-in generator: the fingerprint cache, `force` and a custom template are not used."
+    )]
+    pub async fn generate_manifest_async(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<ManifestParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let project_filter = params.0.project;
+        let jobs = self.jobs.clone();
+
+        let job_id = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let current_fp = ws.get_fingerprint()?;
+            let config = GlobalConfig::load(None)?.unwrap_or_default();
+
+            let reporter = toad_core::NoOpReporter;
+            toad_discovery::sync_registry(&ws, &reporter)?;
+
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+            let projects: Vec<_> = registry
+                .projects
+                .iter()
+                .filter(|p| {
+                    if let Some(f) = &project_filter {
+                        p.name.to_lowercase().contains(&f.to_lowercase())
+                    } else {
+                        true
+                    }
+                })
+                .cloned()
+                .collect();
+
+            if projects.is_empty() {
+                return Err(toad_core::ToadError::Other(
+                    "No projects found matching filter".to_string(),
+                ));
+            }
+
+            let (job_id, cancel_flag) = jobs.register("generate_manifest", projects.len());
+            let job_id_for_task = job_id.clone();
+
+            tokio::task::spawn_blocking(move || {
+                let run = || -> Result<(), toad_core::ToadError> {
+                    ws.ensure_shadows()?;
+
+                    let manifest_md = toad_manifest::generate_markdown(
+                        &projects,
+                        current_fp,
+                        Some(config.budget.ecosystem_tokens),
+                    );
+                    fs::write(ws.manifest_path(), manifest_md)?;
+
+                    let system_prompt = toad_manifest::generate_system_prompt(
+                        &projects,
+                        Some(config.budget.ecosystem_tokens),
+                    );
+                    fs::write(ws.shadows_dir.join("SYSTEM_PROMPT.md"), system_prompt)?;
+
+                    let llms_txt = toad_manifest::generate_llms_txt(&projects);
+                    fs::write(ws.shadows_dir.join("llms.txt"), llms_txt)?;
+
+                    for p in &projects {
+                        if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                            return Err(toad_core::ToadError::Other("Job cancelled".to_string()));
+                        }
+
+                        let proj_shadow_dir = ws.shadows_dir.join(&p.name);
+                        fs::create_dir_all(&proj_shadow_dir)?;
+
+                        let context_md = toad_manifest::generate_project_context_md(
+                            p,
+                            Some(config.budget.project_tokens),
+                        );
+                        fs::write(proj_shadow_dir.join("CONTEXT.md"), context_md)?;
+                        jobs.bump_progress(&job_id_for_task);
+                    }
+
+                    Ok(())
+                };
+
+                jobs.finish(&job_id_for_task, run().map_err(|e| e.to_string()));
+            });
+
+            Ok::<_, toad_core::ToadError>(job_id)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::json!({ "job_id": job_id }).to_string(),
+        )]))
+    }
+
+    #[tool(description = "[Management] Poll the status of a background job started by an async tool such as generate_manifest_async.")]
+    pub async fn get_job_status(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<GetJobStatusParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let job_id = params.0.job_id;
+        let status = self.jobs.get(&job_id).ok_or_else(|| {
+            crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(format!(
+                "No job found with id '{}'",
+                job_id
+            )))
+        })?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&status).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Management] Start a background job by kind (\"project_stats\", \"generate_manifest\", or \"sync_registry\") and return a job id immediately. Poll with get_job_status. Alias of submit_job, kept for backward compatibility."
+    )]
+    pub async fn start_job(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<StartJobParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        self.spawn_job(params.kind, params.query, params.tag)
+    }
+
+    #[tool(
+        description = "[Management] Submit a kind of work ( \"project_stats\", \"generate_manifest\", or \"sync_registry\") to run on a detached task and return a job id immediately, reporting progress through the same job registry as start_job. Poll with get_job_status."
+    )]
+    pub async fn submit_job(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<StartJobParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        self.spawn_job(params.kind, params.query, params.tag)
+    }
+
+    /// Shared dispatch behind `start_job`/`submit_job`: validates `kind`,
+    /// registers a job, and spawns the matching blocking work reporting
+    /// progress through `self.jobs`. The two tools are kept as separate,
+    /// identically-behaving entry points for backward compatibility rather
+    /// than having one silently alias the other's name.
+    fn spawn_job(
+        &self,
+        kind: String,
+        query: Option<String>,
+        tag: Option<String>,
+    ) -> Result<CallToolResult, McpError> {
+        let jobs = self.jobs.clone();
+
+        if !matches!(kind.as_str(), "project_stats" | "generate_manifest" | "sync_registry") {
+            return Err(crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(format!(
+                "Unsupported job kind '{}': expected \"project_stats\", \"generate_manifest\", or \"sync_registry\"",
+                kind
+            ))));
+        }
+
+        let (job_id, cancel_flag) = jobs.register(&kind, 1);
+        let job_id_for_task = job_id.clone();
+        let jobs_for_task = jobs.clone();
+        let kind_for_task = kind.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let run = || -> Result<serde_json::Value, toad_core::ToadError> {
+                let ws = Workspace::discover()?;
+
+                if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                    return Err(toad_core::ToadError::Other("Job cancelled".to_string()));
+                }
+
+                match kind_for_task.as_str() {
+                    "sync_registry" => {
+                        let reporter = crate::jobs::JobReporter::new(
+                            jobs_for_task.clone(),
+                            job_id_for_task.clone(),
+                        );
+                        toad_discovery::sync_registry(&ws, &reporter)?;
+                        Ok(serde_json::json!({ "kind": "sync_registry", "status": "synced" }))
+                    }
+                    "project_stats" => {
+                        let registry =
+                            toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+                        let report = toad_ops::stats::generate_analytics_report(
+                            &registry.projects,
+                            query.as_deref(),
+                            tag.as_deref(),
+                        );
+                        jobs_for_task.bump_progress(&job_id_for_task);
+                        Ok(serde_json::to_value(report).unwrap_or_default())
+                    }
+                    _ => {
+                        let current_fp = ws.get_fingerprint()?;
+                        let config = GlobalConfig::load(None)?.unwrap_or_default();
+                        let registry =
+                            toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+                        let projects: Vec<_> = registry
+                            .projects
+                            .iter()
+                            .filter(|p| {
+                                query
+                                    .as_ref()
+                                    .is_none_or(|q| p.name.to_lowercase().contains(&q.to_lowercase()))
+                            })
+                            .cloned()
+                            .collect();
+                        jobs_for_task.set_total(&job_id_for_task, projects.len());
+
+                        ws.ensure_shadows()?;
+                        let manifest_md = toad_manifest::generate_markdown(
+                            &projects,
+                            current_fp,
+                            Some(config.budget.ecosystem_tokens),
+                        );
+                        fs::write(ws.manifest_path(), manifest_md)?;
+
+                        for p in &projects {
+                            if cancel_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                                return Err(toad_core::ToadError::Other("Job cancelled".to_string()));
+                            }
+
+                            let proj_shadow_dir = ws.shadows_dir.join(&p.name);
+                            fs::create_dir_all(&proj_shadow_dir)?;
+                            let context_md = toad_manifest::generate_project_context_md(
+                                p,
+                                Some(config.budget.project_tokens),
+                            );
+                            fs::write(proj_shadow_dir.join("CONTEXT.md"), context_md)?;
+                            jobs_for_task.bump_progress(&job_id_for_task);
+                        }
+
+                        Ok(serde_json::json!({
+                            "kind": "generate_manifest",
+                            "projects": projects.len(),
+                        }))
+                    }
+                }
+            };
+
+            jobs_for_task.finish_with_result(&job_id_for_task, run().map_err(|e| e.to_string()));
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::json!({ "job_id": job_id }).to_string(),
+        )]))
+    }
+
+    #[tool(description = "[Management] List recent background jobs, most recently started first.")]
+    pub async fn list_jobs(
+        &self,
+        _params: rmcp::handler::server::wrapper::Parameters<NoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let jobs = self.jobs.list();
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&jobs).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Management] Cancel a queued or running background job. Cooperative: the job checks its cancel flag and may take a moment to stop."
+    )]
+    pub async fn cancel_job(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<CancelJobParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let cancelled = self.jobs.cancel(&params.0.job_id);
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::json!({ "cancelled": cancelled }).to_string(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Analysis] Report estimated token usage of the generated manifest/system-prompt/per-project CONTEXT.md files against the configured budget. Flags which projects and the ecosystem total are over budget."
+    )]
+    pub async fn get_budget_report(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<GetBudgetReportParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let query = params.0.query;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let current_fp = ws.get_fingerprint()?;
+            let config = GlobalConfig::load(None)?.unwrap_or_default();
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let projects: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    query
+                        .as_ref()
+                        .is_none_or(|q| p.name.to_lowercase().contains(&q.to_lowercase()))
+                })
+                .collect();
+
+            let manifest_md = toad_manifest::generate_markdown(
+                &projects,
+                current_fp,
+                Some(config.budget.ecosystem_tokens),
+            );
+            let system_prompt = toad_manifest::generate_system_prompt(
+                &projects,
+                Some(config.budget.ecosystem_tokens),
+            );
+
+            let manifest_tokens = toad_manifest::estimate_tokens(&manifest_md);
+            let system_prompt_tokens = toad_manifest::estimate_tokens(&system_prompt);
+            let mut ecosystem_tokens = manifest_tokens + system_prompt_tokens;
+
+            let mut per_project = Vec::new();
+            for p in &projects {
                 let context_md = toad_manifest::generate_project_context_md(
                     p,
                     Some(config.budget.project_tokens),
                 );
-                fs::write(proj_shadow_dir.join("CONTEXT.md"), context_md)?;
+                let tokens = toad_manifest::estimate_tokens(&context_md);
+                ecosystem_tokens += tokens;
+
+                per_project.push(serde_json::json!({
+                    "project": p.name,
+                    "estimated_tokens": tokens,
+                    "budget": config.budget.project_tokens,
+                    "over_budget": tokens > config.budget.project_tokens,
+                }));
             }
 
-            Ok::<_, toad_core::ToadError>(format!(
-                "Manifest and tiered prompts generated for {} projects",
-                projects.len()
-            ))
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "files": {
+                    "manifest_md": manifest_tokens,
+                    "system_prompt_md": system_prompt_tokens,
+                },
+                "projects": per_project,
+                "ecosystem": {
+                    "estimated_tokens": ecosystem_tokens,
+                    "budget": config.budget.ecosystem_tokens,
+                    "over_budget": ecosystem_tokens > config.budget.ecosystem_tokens,
+                },
+            }))
         })
         .await
         .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
         .map_err(crate::errors::toad_error_to_mcp)?;
 
-        Ok(CallToolResult::success(vec![Content::text(result)]))
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
     }
 
     #[tool(
@@ -928,6 +1830,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<RegisterContextParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let name = params.0.name;
         let path = params.0.path;
 
@@ -997,6 +1901,8 @@ impl ToadService {
         &self,
         params: rmcp::handler::server::wrapper::Parameters<TagParams>,
     ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
         let params = params.0;
 
         let result = tokio::task::spawn_blocking(move || {
@@ -1082,27 +1988,2055 @@ impl ToadService {
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
-}
 
-const INSTRUCTIONS: &str = "Toad is an AI-native ecosystem context oracle. \
-It provides tools to query project metadata, search projects semantically, \
-and retrieve high-fidelity architectural context across multiple repositories.";
+    #[tool(
+        description = "[Management] Bootstrap a context from a GitHub org/user: pages through its repos and registers each as a project, optionally cloning them locally."
+    )]
+    pub async fn import_github_org(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<ImportGithubOrgParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
 
-#[async_trait]
-#[tool_handler]
-impl ServerHandler for ToadService {
-    fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            protocol_version: ProtocolVersion::LATEST,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
-            server_info: Implementation {
-                name: "toad-mcp".into(),
-                version: env!("CARGO_PKG_VERSION").into(),
-                icons: None,
-                title: Some("Toad MCP Server".into()),
-                website_url: Some("https://github.com/Primatif/Primatif_Toad".into()),
-            },
-            instructions: Some(INSTRUCTIONS.into()),
-        }
+        let params = params.0;
+        let token = params.token.clone().or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        let clone = params.clone.unwrap_or(false);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let mut config = GlobalConfig::load(None)?.unwrap_or_default();
+
+            let ctx_name = params
+                .context
+                .clone()
+                .or_else(|| config.active_context.clone())
+                .unwrap_or_else(|| "default".to_string());
+            let ctx = config
+                .project_contexts
+                .get(&ctx_name)
+                .ok_or_else(|| toad_core::ToadError::ContextNotFound(ctx_name.clone()))?;
+            let ctx_path = ctx.path.clone();
+
+            let repos: Vec<_> = crate::github::list_org_repos(&params.org, token.as_deref())?
+                .into_iter()
+                .filter(|r| {
+                    params
+                        .topic
+                        .as_ref()
+                        .is_none_or(|t| r.topics.iter().any(|rt| rt.eq_ignore_ascii_case(t)))
+                })
+                .collect();
+
+            let mut registry = toad_core::ProjectRegistry::load(Some(ctx_name.as_str()), None)?;
+            let mut imported = Vec::new();
+            let mut cloned = Vec::new();
+            let mut saw_gitmodules = false;
+
+            for repo in &repos {
+                let target_path = ctx_path.join(&repo.name);
+
+                if clone && !target_path.exists() {
+                    toad_git::clone(&repo.clone_url, &target_path)?;
+                    cloned.push(repo.name.clone());
+                }
+                if clone && target_path.join(".gitmodules").exists() {
+                    saw_gitmodules = true;
+                }
+
+                let mut tags: Vec<String> =
+                    repo.topics.iter().map(|t| format!("#{}", t)).collect();
+                if repo.archived {
+                    tags.push("#archived".to_string());
+                }
+                if repo.fork {
+                    tags.push("#fork".to_string());
+                }
+
+                let project = toad_core::Project {
+                    name: repo.name.clone(),
+                    path: target_path,
+                    stack: repo
+                        .language
+                        .clone()
+                        .unwrap_or_else(|| "unknown".to_string()),
+                    tags,
+                    ..toad_core::Project::default()
+                };
+
+                registry.projects.retain(|p| p.name != project.name);
+                registry.projects.push(project);
+                imported.push(repo.name.clone());
+            }
+
+            registry.save(&ws.registry_path(Some(ctx_name.as_str())))?;
+
+            // Mirror register_context's Hub detection: if cloning turned up a
+            // repo with submodules, the context is a submodule-based hub
+            // rather than a flat collection of projects.
+            let mut upgraded_to_hub = false;
+            if saw_gitmodules
+                && let Some(stored_ctx) = config.project_contexts.get_mut(&ctx_name)
+                && !matches!(stored_ctx.context_type, toad_core::ContextType::Hub)
+            {
+                stored_ctx.context_type = toad_core::ContextType::Hub;
+                upgraded_to_hub = true;
+            }
+            if upgraded_to_hub {
+                config.save(None)?;
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "context": ctx_name,
+                "imported": imported.len(),
+                "cloned": cloned.len(),
+                "projects": imported,
+                "context_type_upgraded_to_hub": upgraded_to_hub,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Management] Run a shell command in every project matching a name/tag filter (a \"foreach\" over the registry), returning per-project stdout/stderr/exit status."
+    )]
+    pub async fn run_across_projects(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<RunAcrossProjectsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            if targets.is_empty() {
+                return Ok::<_, toad_core::ToadError>(
+                    "No projects found matching filters.".to_string(),
+                );
+            }
+
+            let fail_fast = params.fail_fast.unwrap_or(false);
+            let command = params.command.clone();
+            let aborted = std::sync::atomic::AtomicBool::new(false);
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                if fail_fast && aborted.load(std::sync::atomic::Ordering::Relaxed) {
+                    return serde_json::json!({"project": p.name, "skipped": true});
+                }
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .current_dir(&p.path)
+                    .output();
+
+                let report = match output {
+                    Ok(o) => serde_json::json!({
+                        "project": p.name,
+                        "exit_code": o.status.code(),
+                        "stdout": String::from_utf8_lossy(&o.stdout),
+                        "stderr": String::from_utf8_lossy(&o.stderr),
+                    }),
+                    Err(e) => serde_json::json!({
+                        "project": p.name,
+                        "exit_code": null,
+                        "error": e.to_string(),
+                    }),
+                };
+                if fail_fast
+                    && report
+                        .get("exit_code")
+                        .and_then(|c| c.as_i64())
+                        .map(|c| c != 0)
+                        .unwrap_or(true)
+                {
+                    aborted.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                report
+            };
+
+            let reports: Vec<serde_json::Value> = if params.parallel.unwrap_or(false) {
+                // Bounded the same way as the other "run across projects" pools
+                // (spawn_in_projects, spawn_command): one OS thread per project
+                // is unbounded and would starve the box on a large registry.
+                let parallelism = std::thread::available_parallelism().map_or(4, |n| n.get());
+                let mut reports = Vec::with_capacity(targets.len());
+                for chunk in targets.chunks(parallelism) {
+                    let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                        chunk
+                            .iter()
+                            .map(|p| scope.spawn(|| run_one(p)))
+                            .collect::<Vec<_>>()
+                            .into_iter()
+                            .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"error": "panicked"})))
+                            .collect()
+                    });
+                    reports.extend(chunk_reports);
+                }
+                reports
+            } else {
+                let mut reports = Vec::new();
+                for p in &targets {
+                    if fail_fast && aborted.load(std::sync::atomic::Ordering::Relaxed) {
+                        break;
+                    }
+                    reports.push(run_one(p));
+                }
+                reports
+            };
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Report which registered projects a git diff range touched (monorail-style change mapping). Files outside any registered project are grouped under '_unmapped'; nested projects resolve to the deepest matching prefix."
+    )]
+    pub async fn get_affected_projects(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<GetAffectedProjectsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let base = params.0.base.unwrap_or_else(|| "HEAD~1".to_string());
+        let head = params.0.head.unwrap_or_else(|| "HEAD".to_string());
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let relative: Vec<(String, std::path::PathBuf)> = registry
+                .projects
+                .iter()
+                .filter_map(|p| {
+                    p.path
+                        .strip_prefix(&ws.projects_dir)
+                        .ok()
+                        .map(|rel| (p.name.clone(), rel.to_path_buf()))
+                })
+                .collect();
+            let trie = crate::pathtrie::PathTrie::build(
+                relative.iter().map(|(n, p)| (n.as_str(), p.as_path())),
+            );
+
+            let range = format!("{}..{}", base, head);
+            let output = std::process::Command::new("git")
+                .args(["diff", "--name-only", &range])
+                .current_dir(&ws.projects_dir)
+                .output()
+                .map_err(|e| toad_core::ToadError::Other(format!("Failed to run git diff: {}", e)))?;
+
+            if !output.status.success() {
+                return Err(toad_core::ToadError::Other(format!(
+                    "git diff failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                )));
+            }
+
+            let mut by_project: std::collections::HashMap<String, usize> =
+                std::collections::HashMap::new();
+            let mut unmapped = Vec::new();
+
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                let path = std::path::Path::new(line);
+                match trie.lookup(path) {
+                    Some(project) => *by_project.entry(project.to_string()).or_insert(0) += 1,
+                    None => unmapped.push(line.to_string()),
+                }
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "range": range,
+                "affected": by_project.keys().cloned().collect::<Vec<_>>(),
+                "changed_files_by_project": by_project,
+                "_unmapped": unmapped,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Management] Reconcile the registry against a remote org: clone repos present remotely but missing on disk, and report local directories that look like projects but aren't registered. Dry-run by default; pass apply:true to actually clone."
+    )]
+    pub async fn sync_workspace(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<SyncWorkspaceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let token = params.token.or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        let apply = params.apply.unwrap_or(false);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+            let registered_names: std::collections::HashSet<_> =
+                registry.projects.iter().map(|p| p.name.clone()).collect();
+            let registered_paths: std::collections::HashSet<_> =
+                registry.projects.iter().map(|p| p.path.clone()).collect();
+
+            let mut to_clone = Vec::new();
+            let mut already_present = Vec::new();
+
+            if let Some(org) = &params.org {
+                let repos = crate::github::list_org_repos(org, token.as_deref())?;
+                for repo in repos {
+                    let target = ws.projects_dir.join(&repo.name);
+                    if target.exists() || registered_names.contains(&repo.name) {
+                        already_present.push(repo.name);
+                    } else {
+                        to_clone.push((repo.name, repo.clone_url, target));
+                    }
+                }
+            }
+
+            let mut to_register = Vec::new();
+            if ws.projects_dir.exists() {
+                for entry in fs::read_dir(&ws.projects_dir)? {
+                    let entry = entry?;
+                    let path = entry.path();
+                    if path.join(".git").exists() && !registered_paths.contains(&path) {
+                        to_register.push(path.display().to_string());
+                    }
+                }
+            }
+
+            if !apply {
+                return Ok::<_, toad_core::ToadError>(serde_json::json!({
+                    "dry_run": true,
+                    "to_clone": to_clone.iter().map(|(n, _, _)| n).collect::<Vec<_>>(),
+                    "to_register": to_register,
+                    "already_present": already_present,
+                }));
+            }
+
+            let mut cloned = Vec::new();
+            let mut errors = Vec::new();
+            for (name, clone_url, target) in &to_clone {
+                match toad_git::clone(clone_url, target) {
+                    Ok(()) => cloned.push(name.clone()),
+                    Err(e) => errors.push(serde_json::json!({"project": name, "error": e.to_string()})),
+                }
+            }
+
+            let reporter = toad_core::NoOpReporter;
+            toad_discovery::sync_registry(&ws, &reporter)?;
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "dry_run": false,
+                "cloned": cloned,
+                "to_register": to_register,
+                "already_present": already_present,
+                "errors": errors,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Management] Bootstrap a context on a new machine: list a GitHub org's repos (or take an explicit manifest of clone URLs), diff against what's already on disk, and clone the missing ones in — as submodules for a Hub context, as plain clones under projects/ for a Pond. Registers the result via sync_registry afterward."
+    )]
+    pub async fn clone_missing(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<CloneMissingParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let token = params.token.or_else(|| std::env::var("GITHUB_TOKEN").ok());
+        let use_ssh = params.protocol.as_deref() == Some("ssh");
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let config = GlobalConfig::load(None)?.unwrap_or_default();
+
+            let ctx_name = params
+                .context
+                .clone()
+                .or_else(|| config.active_context.clone())
+                .unwrap_or_else(|| "default".to_string());
+            let ctx = config
+                .project_contexts
+                .get(&ctx_name)
+                .ok_or_else(|| toad_core::ToadError::ContextNotFound(ctx_name.clone()))?;
+            let ctx_path = ctx.path.clone();
+            let is_hub = matches!(ctx.context_type, toad_core::ContextType::Hub);
+
+            // (name, clone_url) pairs, from either an explicit manifest or an org listing.
+            let candidates: Vec<(String, String)> = if let Some(manifest) = &params.manifest {
+                manifest
+                    .iter()
+                    .map(|url| {
+                        let name = url
+                            .rsplit('/')
+                            .next()
+                            .unwrap_or(url)
+                            .trim_end_matches(".git")
+                            .to_string();
+                        (name, url.clone())
+                    })
+                    .collect()
+            } else if let Some(org) = &params.org {
+                crate::github::list_org_repos(org, token.as_deref())?
+                    .into_iter()
+                    .map(|repo| {
+                        let url = if use_ssh { repo.ssh_url } else { repo.clone_url };
+                        (repo.name, url)
+                    })
+                    .collect()
+            } else {
+                return Err(toad_core::ToadError::Other(
+                    "Either `org` or `manifest` must be provided".to_string(),
+                ));
+            };
+
+            let dest_root = if is_hub { ctx_path.clone() } else { ctx_path.join("projects") };
+            fs::create_dir_all(&dest_root)?;
+
+            let mut cloned = Vec::new();
+            let mut already_present = Vec::new();
+            let mut errors = Vec::new();
+
+            for (name, clone_url) in &candidates {
+                let target = dest_root.join(name);
+                if target.exists() {
+                    already_present.push(name.clone());
+                    continue;
+                }
+
+                let outcome = if is_hub {
+                    std::process::Command::new("git")
+                        .args(["submodule", "add", clone_url, name])
+                        .current_dir(&ctx_path)
+                        .output()
+                        .map_err(|e| toad_core::ToadError::Other(e.to_string()))
+                        .and_then(|o| {
+                            if o.status.success() {
+                                Ok(())
+                            } else {
+                                Err(toad_core::ToadError::Other(
+                                    String::from_utf8_lossy(&o.stderr).into_owned(),
+                                ))
+                            }
+                        })
+                } else {
+                    toad_git::clone(clone_url, &target)
+                };
+
+                match outcome {
+                    Ok(()) => cloned.push(name.clone()),
+                    Err(e) => errors.push(serde_json::json!({"project": name, "error": e.to_string()})),
+                }
+            }
+
+            let reporter = toad_core::NoOpReporter;
+            toad_discovery::sync_registry(&ws, &reporter)?;
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "context": ctx_name,
+                "layout": if is_hub { "hub" } else { "pond" },
+                "cloned": cloned,
+                "already_present": already_present,
+                "errors": errors,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Discovery] Apply a git action (fetch, pull, checkout, switch) across every project matching query/tag filters. Runs concurrently and never aborts the whole call on a single repo's failure."
+    )]
+    pub async fn run_git_action(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<RunGitActionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let action = params.action.clone();
+            let branch = params.branch.clone();
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                let outcome = match action.as_str() {
+                    "fetch" => toad_git::fetch(&p.path).map(|_| "fetched".to_string()),
+                    "pull" => toad_git::pull(&p.path).map(|_| "pulled".to_string()),
+                    "checkout" | "switch" => match &branch {
+                        None => {
+                            return serde_json::json!({
+                                "project": p.name,
+                                "result": "error",
+                                "error": "branch is required for checkout/switch",
+                            });
+                        }
+                        Some(b) => {
+                            let local = toad_git::branches::list_local_branches(&p.path)
+                                .unwrap_or_default();
+                            if !local.iter().any(|existing| existing == b) {
+                                return serde_json::json!({
+                                    "project": p.name,
+                                    "result": "skipped",
+                                    "reason": format!("branch '{}' not found", b),
+                                });
+                            }
+                            toad_git::checkout(&p.path, b).map(|_| format!("on {}", b))
+                        }
+                    },
+                    other => {
+                        return serde_json::json!({
+                            "project": p.name,
+                            "result": "error",
+                            "error": format!("unknown action '{}'", other),
+                        });
+                    }
+                };
+
+                match outcome {
+                    Ok(msg) => serde_json::json!({"project": p.name, "result": "success", "detail": msg}),
+                    Err(e) => serde_json::json!({"project": p.name, "result": "error", "error": e.to_string()}),
+                }
+            };
+
+            let parallelism = std::thread::available_parallelism().map_or(4, |n| n.get());
+            let mut reports = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(parallelism) {
+                let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                    chunk
+                        .iter()
+                        .map(|p| scope.spawn(|| run_one(p)))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"result": "panicked"})))
+                        .collect()
+                });
+                reports.extend(chunk_reports);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Fetch, fast-forward pull, and optionally push across every project matched by query/tag filters. Skips repos with uncommitted changes and repos whose branch has diverged from its upstream rather than forcing anything."
+    )]
+    pub async fn git_sync(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<GitSyncParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let push = params.push.unwrap_or(false);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                match toad_git::is_dirty(&p.path) {
+                    Ok(true) => {
+                        return serde_json::json!({
+                            "project": p.name,
+                            "action": "skipped",
+                            "reason": "uncommitted changes",
+                        });
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        return serde_json::json!({
+                            "project": p.name,
+                            "action": "error",
+                            "error": e.to_string(),
+                        });
+                    }
+                }
+
+                if let Err(e) = toad_git::fetch(&p.path) {
+                    return serde_json::json!({
+                        "project": p.name,
+                        "action": "error",
+                        "error": e.to_string(),
+                    });
+                }
+
+                let (ahead, behind) = match toad_git::ahead_behind(&p.path) {
+                    Ok(pair) => pair,
+                    Err(e) => {
+                        return serde_json::json!({
+                            "project": p.name,
+                            "action": "error",
+                            "error": e.to_string(),
+                        });
+                    }
+                };
+
+                if ahead > 0 && behind > 0 {
+                    return serde_json::json!({
+                        "project": p.name,
+                        "action": "diverged",
+                        "ahead": ahead,
+                        "behind": behind,
+                    });
+                }
+
+                let mut actions = vec!["fetched".to_string()];
+
+                if behind > 0 {
+                    if let Err(e) = toad_git::pull(&p.path) {
+                        return serde_json::json!({
+                            "project": p.name,
+                            "action": "error",
+                            "error": e.to_string(),
+                        });
+                    }
+                    actions.push("fast_forwarded".to_string());
+                }
+
+                if push && ahead > 0 {
+                    match toad_git::push(&p.path) {
+                        Ok(_) => actions.push("pushed".to_string()),
+                        Err(e) => {
+                            return serde_json::json!({
+                                "project": p.name,
+                                "action": "error",
+                                "actions": actions,
+                                "error": e.to_string(),
+                            });
+                        }
+                    }
+                }
+
+                serde_json::json!({
+                    "project": p.name,
+                    "action": "synced",
+                    "actions": actions,
+                })
+            };
+
+            let parallelism = std::thread::available_parallelism().map_or(4, |n| n.get());
+            let mut reports = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(parallelism) {
+                let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                    chunk
+                        .iter()
+                        .map(|p| scope.spawn(|| run_one(p)))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"action": "panicked"})))
+                        .collect()
+                });
+                reports.extend(chunk_reports);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Discovery] Run an argv-form command (no shell string, to avoid injection) in every project matching query/tag filters. Bounded parallelism, per-command timeout, and per-stream output limits so a noisy build can't blow the response size."
+    )]
+    pub async fn spawn_in_projects(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<SpawnInProjectsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        if params.command.is_empty() {
+            return Err(crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(
+                "command must have at least one element (the program)".to_string(),
+            )));
+        }
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let parallelism = params
+                .parallelism
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, |n| n.get()))
+                .max(1);
+            let timeout = std::time::Duration::from_secs(params.timeout_secs.unwrap_or(120));
+            let fail_fast = params.fail_fast.unwrap_or(false);
+            let command = params.command.clone();
+            let aborted = std::sync::atomic::AtomicBool::new(false);
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                if fail_fast && aborted.load(std::sync::atomic::Ordering::Relaxed) {
+                    return serde_json::json!({"project": p.name, "skipped": true});
+                }
+                match crate::procrun::run(&command, &p.path, timeout, crate::procrun::DEFAULT_STREAM_LIMIT) {
+                    Ok(o) => {
+                        if fail_fast && o.exit_code != Some(0) {
+                            aborted.store(true, std::sync::atomic::Ordering::Relaxed);
+                        }
+                        serde_json::json!({
+                            "project": p.name,
+                            "exit_code": o.exit_code,
+                            "stdout": o.stdout,
+                            "stdout_truncated": o.stdout_truncated,
+                            "stderr": o.stderr,
+                            "timed_out": o.timed_out,
+                        })
+                    }
+                    Err(e) => serde_json::json!({"project": p.name, "error": e.to_string()}),
+                }
+            };
+
+            let mut reports = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(parallelism) {
+                let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                    chunk
+                        .iter()
+                        .map(|p| scope.spawn(|| run_one(p)))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"error": "panicked"})))
+                        .collect()
+                });
+                reports.extend(chunk_reports);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Run a shell command string across every project matched by query/tag filters using a bounded worker pool (default: number of CPUs). Returns per-project exit code, stdout/stderr, and duration."
+    )]
+    pub async fn spawn_command(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<SpawnCommandParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let concurrency = params
+                .concurrency
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, |n| n.get()))
+                .max(1);
+            let timeout = std::time::Duration::from_secs(params.timeout_secs.unwrap_or(120));
+            let argv = vec!["sh".to_string(), "-c".to_string(), params.command.clone()];
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                match crate::procrun::run(&argv, &p.path, timeout, crate::procrun::DEFAULT_STREAM_LIMIT) {
+                    Ok(o) => serde_json::json!({
+                        "project": p.name,
+                        "exit_code": o.exit_code,
+                        "stdout": o.stdout,
+                        "stdout_truncated": o.stdout_truncated,
+                        "stderr": o.stderr,
+                        "duration_ms": o.duration_ms,
+                        "timed_out": o.timed_out,
+                    }),
+                    Err(e) => serde_json::json!({"project": p.name, "error": e.to_string()}),
+                }
+            };
+
+            let mut reports = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(concurrency) {
+                let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                    chunk
+                        .iter()
+                        .map(|p| scope.spawn(|| run_one(p)))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"error": "panicked"})))
+                        .collect()
+                });
+                reports.extend(chunk_reports);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Fetch (or, with pull:true, pull) every existing repo matched by query/tag filters, optionally fetching submodules too. A project whose directory is missing is reported (`would_clone` in a dry run, an `error` otherwise) and is not cloned. Dry-run by default; pass dry_run:false to mutate."
+    )]
+    pub async fn sync_projects(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<SyncProjectsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let dry_run = params.dry_run.unwrap_or(true);
+        let do_pull = params.pull.unwrap_or(false);
+        let recurse = params.recurse_submodules.unwrap_or(false);
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let mut reports = Vec::with_capacity(targets.len());
+            for p in &targets {
+                if !p.path.exists() {
+                    if dry_run {
+                        reports.push(serde_json::json!({"project": p.name, "action": "would_clone"}));
+                    } else {
+                        reports.push(serde_json::json!({
+                            "project": p.name,
+                            "action": "error",
+                            "message": "project directory missing and no clone URL on record",
+                        }));
+                    }
+                    continue;
+                }
+
+                if dry_run {
+                    reports.push(serde_json::json!({"project": p.name, "action": "would_fetch"}));
+                    continue;
+                }
+
+                let action = if do_pull {
+                    toad_git::pull(&p.path).map(|_| "pulled")
+                } else {
+                    toad_git::fetch(&p.path).map(|_| "fetched")
+                };
+
+                match action {
+                    Ok(label) => {
+                        if recurse {
+                            for sub in &p.submodules {
+                                let _ = toad_git::fetch(&p.path.join(&sub.name));
+                            }
+                        }
+                        reports.push(serde_json::json!({"project": p.name, "action": label}));
+                    }
+                    Err(e) => reports.push(serde_json::json!({
+                        "project": p.name,
+                        "action": "error",
+                        "message": e.to_string(),
+                    })),
+                }
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Run a shell command across projects matched by query/tag filters, skipping projects whose command + tracked-file fingerprint hasn't changed since the last successful run. Non-cached projects run concurrently via a bounded worker pool; pass force:true to bypass the cache."
+    )]
+    pub async fn run_task(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<RunTaskParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let targets: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let concurrency = params
+                .concurrency
+                .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, |n| n.get()))
+                .max(1);
+            let timeout = std::time::Duration::from_secs(params.timeout_secs.unwrap_or(120));
+            let force = params.force.unwrap_or(false);
+            let argv = vec!["sh".to_string(), "-c".to_string(), params.command.clone()];
+            let command = params.command.clone();
+            let cache_root = ws.shadows_dir.join("task-cache");
+
+            let run_one = |p: &toad_core::Project| -> serde_json::Value {
+                let cache_file = cache_root.join(&p.name).join("last_run.json");
+                let hash = task_fingerprint(&command, &p.path);
+
+                if !force
+                    && let Some(cached) = fs::read_to_string(&cache_file)
+                        .ok()
+                        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+                    && cached.get("hash").and_then(|h| h.as_str()) == Some(hash.as_str())
+                {
+                    return serde_json::json!({
+                        "project": p.name,
+                        "status": "cached",
+                        "hash": hash,
+                    });
+                }
+
+                let start = std::time::Instant::now();
+                match crate::procrun::run(&argv, &p.path, timeout, crate::procrun::DEFAULT_STREAM_LIMIT) {
+                    Ok(o) => {
+                        let status = if o.exit_code == Some(0) { "ran" } else { "failed" };
+                        if status == "ran"
+                            && let Some(parent) = cache_file.parent()
+                        {
+                            let _ = fs::create_dir_all(parent);
+                            let _ = fs::write(
+                                &cache_file,
+                                serde_json::json!({
+                                    "hash": hash,
+                                    "stdout": o.stdout,
+                                    "stderr": o.stderr,
+                                })
+                                .to_string(),
+                            );
+                        }
+                        serde_json::json!({
+                            "project": p.name,
+                            "status": status,
+                            "hash": hash,
+                            "duration_ms": o.duration_ms,
+                            "exit_code": o.exit_code,
+                            "stdout": o.stdout,
+                            "stdout_truncated": o.stdout_truncated,
+                            "stderr": o.stderr,
+                            "timed_out": o.timed_out,
+                        })
+                    }
+                    Err(e) => serde_json::json!({
+                        "project": p.name,
+                        "status": "failed",
+                        "hash": hash,
+                        "duration_ms": start.elapsed().as_millis(),
+                        "error": e.to_string(),
+                    }),
+                }
+            };
+
+            let mut reports = Vec::with_capacity(targets.len());
+            for chunk in targets.chunks(concurrency) {
+                let chunk_reports: Vec<_> = std::thread::scope(|scope| {
+                    chunk
+                        .iter()
+                        .map(|p| scope.spawn(|| run_one(p)))
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|h| h.join().unwrap_or_else(|_| serde_json::json!({"error": "panicked"})))
+                        .collect()
+                });
+                reports.extend(chunk_reports);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&reports)?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Resolve a dependency-ordered build plan across projects using submodule relationships and declared DNA dependencies. Groups projects into levels that can be built in parallel; reports cycles explicitly."
+    )]
+    pub async fn resolve_build_order(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<ResolveBuildOrderParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let projects: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let mut depends_on: std::collections::HashMap<String, Vec<String>> =
+                std::collections::HashMap::new();
+            for p in &projects {
+                let mut deps: Vec<String> =
+                    p.submodules.iter().map(|s| s.name.clone()).collect();
+                deps.extend(p.dna.depends_on.iter().cloned());
+                depends_on.insert(p.name.clone(), deps);
+            }
+
+            let topo = crate::graph::topo_levels(&depends_on);
+
+            if !topo.cycle.is_empty() {
+                return Err(toad_core::ToadError::Other(format!(
+                    "Cyclic dependency detected among: {}",
+                    topo.cycle.join(", ")
+                )));
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "levels": topo.levels,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Analysis] Resolve a migration plan ordering projects so dependencies migrate before dependents, derived from submodule references and DNA roles/capabilities that name another project. Cycles are surfaced as a `cycles` field instead of failing."
+    )]
+    pub async fn resolve_migration_order(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<ResolveMigrationOrderParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+
+            let projects: Vec<_> = registry
+                .projects
+                .into_iter()
+                .filter(|p| {
+                    if let Some(q) = &params.query
+                        && !p.name.to_lowercase().contains(&q.to_lowercase())
+                    {
+                        return false;
+                    }
+                    if let Some(t) = &params.tag
+                        && !p
+                            .tags
+                            .iter()
+                            .any(|tag| tag.to_lowercase() == t.to_lowercase())
+                    {
+                        return false;
+                    }
+                    true
+                })
+                .collect();
+
+            let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+
+            let mut depends_on: std::collections::HashMap<String, Vec<String>> =
+                std::collections::HashMap::new();
+            for p in &projects {
+                let mut deps: Vec<String> =
+                    p.submodules.iter().map(|s| s.name.clone()).collect();
+
+                for other in &names {
+                    if *other == p.name {
+                        continue;
+                    }
+                    let mentions_other = p
+                        .dna
+                        .roles
+                        .iter()
+                        .chain(p.dna.capabilities.iter())
+                        .any(|mention| mentions_word(mention, other));
+                    if mentions_other {
+                        deps.push(other.to_string());
+                    }
+                }
+
+                deps.dedup();
+                depends_on.insert(p.name.clone(), deps);
+            }
+
+            let topo = crate::graph::topo_levels(&depends_on);
+
+            let mut stages = topo.levels;
+            let cycles = if topo.cycle.is_empty() {
+                Vec::new()
+            } else {
+                let sccs = crate::graph::strongly_connected(&depends_on, &topo.cycle);
+                let cycle_members: std::collections::HashSet<&str> =
+                    sccs.iter().flatten().map(|s| s.as_str()).collect();
+                for scc in &sccs {
+                    stages.push(scc.clone());
+                }
+
+                // Nodes that merely depend on a cycle member (without being
+                // cyclic themselves) never reach in-degree 0 either, so
+                // `topo.cycle` also holds them, but `strongly_connected`
+                // rightly excludes them from any SCC. Re-run the topo sort
+                // over just these leftovers, treating edges into the cycle as
+                // satisfied (the cycle's own stage above is assumed done),
+                // so they land in `stages` instead of vanishing entirely.
+                let blocked: Vec<&String> = topo
+                    .cycle
+                    .iter()
+                    .filter(|n| !cycle_members.contains(n.as_str()))
+                    .collect();
+                if !blocked.is_empty() {
+                    let blocked_depends_on: std::collections::HashMap<String, Vec<String>> =
+                        blocked
+                            .iter()
+                            .map(|n| {
+                                let deps = depends_on[n.as_str()]
+                                    .iter()
+                                    .filter(|d| !cycle_members.contains(d.as_str()))
+                                    .cloned()
+                                    .collect();
+                                ((*n).clone(), deps)
+                            })
+                            .collect();
+                    stages.extend(crate::graph::topo_levels(&blocked_depends_on).levels);
+                }
+
+                sccs
+            };
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "stages": stages,
+                "cycles": cycles,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Analysis] Run a declarative JSON workload file (an ordered list of analytics invocations with optional repeat counts) and report per-step min/median/p95/max latency, result size, an approximate RSS-delta allocation proxy, and a run-level summary. If the workload names a `baseline` report (as saved by a prior run_workload call), computes the percentage median-latency change per step and flags any that exceed `regression_threshold_pct` (default 20%)."
+    )]
+    pub async fn run_workload(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<RunWorkloadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let threshold_pct = params.regression_threshold_pct.unwrap_or(20.0);
+        let metrics = self.metrics.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let raw = std::fs::read_to_string(&params.workload_path).map_err(|e| {
+                toad_core::ToadError::Other(format!(
+                    "Failed to read workload '{}': {}",
+                    params.workload_path, e
+                ))
+            })?;
+            let workload: crate::bench::Workload = serde_json::from_str(&raw).map_err(|e| {
+                toad_core::ToadError::Other(format!("Invalid workload file: {}", e))
+            })?;
+
+            let registry = toad_core::ProjectRegistry::load(
+                Workspace::discover()?.active_context.as_deref(),
+                None,
+            )?;
+
+            let mut steps = Vec::with_capacity(workload.steps.len());
+            for step in &workload.steps {
+                let repeat = step.repeat.max(1);
+                let mut samples_ms = Vec::with_capacity(repeat);
+                let mut last_bytes = 0;
+                let rss_before = crate::bench::read_rss_kb();
+                let mut peak_rss_delta_kb = None;
+                for _ in 0..repeat {
+                    let start = std::time::Instant::now();
+                    let value = run_analytics_step(&registry, &step.tool, &step.args)?;
+                    let step_ms = start.elapsed().as_secs_f64() * 1000.0;
+                    samples_ms.push(step_ms);
+                    last_bytes = serde_json::to_vec(&value)?.len();
+                    metrics.record(&step.tool, step_ms, step_ms, last_bytes, false);
+
+                    if let (Some(before), Some(after)) = (rss_before, crate::bench::read_rss_kb())
+                    {
+                        let delta = after - before;
+                        peak_rss_delta_kb = Some(peak_rss_delta_kb.unwrap_or(0).max(delta));
+                    }
+                }
+                steps.push(crate::bench::summarize(
+                    step.tool.clone(),
+                    samples_ms,
+                    last_bytes,
+                    peak_rss_delta_kb,
+                ));
+            }
+
+            let summary = crate::bench::summarize_run(&steps);
+            let report = crate::bench::WorkloadReport {
+                name: workload.name.clone(),
+                steps,
+                summary,
+            };
+
+            let comparisons = match &workload.baseline {
+                Some(path) => {
+                    let baseline_raw = std::fs::read_to_string(path).map_err(|e| {
+                        toad_core::ToadError::Other(format!(
+                            "Failed to read baseline '{}': {}",
+                            path, e
+                        ))
+                    })?;
+                    let baseline: crate::bench::WorkloadReport = serde_json::from_str(&baseline_raw)
+                        .map_err(|e| {
+                            toad_core::ToadError::Other(format!("Invalid baseline report: {}", e))
+                        })?;
+                    Some(crate::bench::compare_to_baseline(
+                        &report.steps,
+                        &baseline,
+                        threshold_pct,
+                    ))
+                }
+                None => None,
+            };
+
+            Ok::<_, toad_core::ToadError>(serde_json::json!({
+                "report": report,
+                "comparisons": comparisons,
+            }))
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap_or_default(),
+        )]))
+    }
+
+    #[tool(
+        description = "[Analysis] Commit velocity (commits/week, active days) per project over a trailing window, computed with a bounded worker pool across the registry. A project whose analysis fails reports an `error` entry instead of failing the whole call."
+    )]
+    pub async fn analyze_velocity(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<AnalyzeVelocityParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let days = params.days.unwrap_or(30);
+
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+                let targets = filter_projects(registry.projects, &params.query, &params.tag);
+
+                let results = parallel_analyze_projects(&targets, params.concurrency, |p| {
+                    Ok(serde_json::to_value(toad_ops::analytics::analyze_velocity(&p.path, days)?)?)
+                });
+
+                Ok(serde_json::to_string_pretty(&results)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "analyze_velocity",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Technical-debt signals (TODO/FIXME density, stale branches, etc.) per project, computed with a bounded worker pool across the registry. A project whose analysis fails reports an `error` entry instead of failing the whole call."
+    )]
+    pub async fn analyze_debt(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<AnalyzeDebtParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+                let targets = filter_projects(registry.projects, &params.query, &params.tag);
+
+                let results = parallel_analyze_projects(&targets, params.concurrency, |p| {
+                    Ok(serde_json::to_value(toad_ops::analytics::analyze_debt(&p.path)?)?)
+                });
+
+                Ok(serde_json::to_string_pretty(&results)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "analyze_debt",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Composite health score per project, computed with a bounded worker pool across the registry. A project whose analysis fails reports an `error` entry instead of failing the whole call."
+    )]
+    pub async fn analyze_health(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<AnalyzeHealthParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let registry = toad_core::ProjectRegistry::load(ws.active_context.as_deref(), None)?;
+                let targets = filter_projects(registry.projects, &params.query, &params.tag);
+
+                let results = parallel_analyze_projects(&targets, params.concurrency, |p| {
+                    Ok(serde_json::to_value(toad_ops::analytics::calculate_health_score(p)?)?)
+                });
+
+                Ok(serde_json::to_string_pretty(&results)?)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "analyze_health",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Return the current metrics snapshot (per-tool invocation/error counters, blocking vs. total latency histograms, and result-size histogram) recorded by the analytics tools in this module. `format` selects \"json\" (default) or \"prometheus\" text exposition."
+    )]
+    pub async fn get_metrics(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<GetMetricsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let format = params.0.format.unwrap_or_else(|| "json".to_string());
+
+        let text = match format.as_str() {
+            "prometheus" => self.metrics.snapshot_prometheus(),
+            "json" => serde_json::to_string_pretty(&self.metrics.snapshot_json()).unwrap_or_default(),
+            other => {
+                return Err(crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(format!(
+                    "Unknown metrics format '{}': expected \"json\" or \"prometheus\"",
+                    other
+                ))));
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Run several analytics sub-requests (any mix of \"analyze_velocity\", \"analyze_debt\", \"analyze_health\", \"analyze_deps\", \"get_project_stats\") against one shared registry load, keyed by caller-chosen id. A sub-request that fails reports `{\"error\": ...}` under its id instead of failing the whole call."
+    )]
+    pub async fn batch_analyze(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<BatchAnalyzeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let metrics = self.metrics.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let mut seen_ids = std::collections::HashSet::with_capacity(params.requests.len());
+            for req in &params.requests {
+                if !seen_ids.insert(req.id.as_str()) {
+                    return Err(toad_core::ToadError::Other(format!(
+                        "Duplicate batch_analyze request id '{}'",
+                        req.id
+                    )));
+                }
+            }
+
+            let registry = toad_core::ProjectRegistry::load(
+                Workspace::discover()?.active_context.as_deref(),
+                None,
+            )?;
+
+            let mut results = serde_json::Map::with_capacity(params.requests.len());
+            for req in &params.requests {
+                let step_start = std::time::Instant::now();
+                let (value, is_err) = match run_analytics_step(&registry, &req.tool, &req.args) {
+                    Ok(v) => (v, false),
+                    Err(e) => (serde_json::json!({ "error": e.to_string() }), true),
+                };
+                let step_ms = step_start.elapsed().as_secs_f64() * 1000.0;
+                metrics.record(
+                    &req.tool,
+                    step_ms,
+                    step_ms,
+                    serde_json::to_vec(&value).map(|b| b.len()).unwrap_or(0),
+                    is_err,
+                );
+                results.insert(req.id.clone(), value);
+            }
+
+            Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&serde_json::Value::Object(
+                results,
+            ))?)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Ecosystem-wide activity/health trend report over the commit history window. Caches the result (and its digest) so watch_trends can long-poll for changes instead of recomputing on every call."
+    )]
+    pub async fn analyze_trends(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<AnalyzeTrendsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let days = params.0.days.unwrap_or(90);
+        let trends = self.trends.clone();
+
+        let total_start = std::time::Instant::now();
+        let (value, blocking_ms) = tokio::task::spawn_blocking(move || {
+            let blocking_start = std::time::Instant::now();
+            let out: Result<String, toad_core::ToadError> = (|| {
+                let ws = Workspace::discover()?;
+                let report = toad_ops::analytics::analyze_trends(&ws.projects_dir, days)?;
+                let report_json = serde_json::to_string_pretty(&report)?;
+                trends.put(&ws.projects_dir.to_string_lossy(), days, report_json.clone());
+                Ok(report_json)
+            })();
+            (out, blocking_start.elapsed().as_secs_f64() * 1000.0)
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?;
+
+        self.metrics.record(
+            "analyze_trends",
+            blocking_ms,
+            total_start.elapsed().as_secs_f64() * 1000.0,
+            value.as_ref().map(|s| s.len()).unwrap_or(0),
+            value.is_err(),
+        );
+        let result = value.map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Analysis] Long-poll layered on analyze_trends: blocks for up to timeout_secs re-checking the trend report, returning as soon as its digest differs from since_digest (or immediately, if since_digest is omitted). On timeout, returns `{\"unchanged\": true, \"digest\": ...}` so the caller can re-poll with the same digest instead of busy-looping full recomputation."
+    )]
+    pub async fn watch_trends(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<WatchTrendsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        self.auth.ensure_authenticated().map_err(crate::errors::toad_error_to_mcp)?;
+
+        let params = params.0;
+        let days = params.days.unwrap_or(90);
+        let timeout = std::time::Duration::from_secs(params.timeout_secs.unwrap_or(30).min(120));
+        let poll_interval = std::time::Duration::from_secs(2);
+        let trends = self.trends.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            let ws = Workspace::discover()?;
+            let projects_dir = ws.projects_dir.to_string_lossy().to_string();
+            let deadline = std::time::Instant::now() + timeout;
+
+            let cached = trends.get(&projects_dir, days);
+            if let Some((digest, report_json)) = &cached
+                && params.since_digest.as_deref() != Some(digest.as_str())
+            {
+                return Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&serde_json::json!({
+                    "digest": digest,
+                    "report": serde_json::from_str::<serde_json::Value>(report_json)?,
+                }))?);
+            }
+
+            // The cheap workspace fingerprint (path/stack/tags/submodules/HEAD
+            // per project) stands in for "did anything worth re-scanning
+            // change"; full `analyze_trends` only re-runs when it moves,
+            // instead of re-scanning every repo on every poll tick.
+            let mut last_fp = ws.get_fingerprint()?;
+            let mut last_digest = cached.map(|(digest, _)| digest);
+            let mut first_pass = true;
+
+            loop {
+                let current_fp = ws.get_fingerprint()?;
+                if first_pass || current_fp != last_fp {
+                    first_pass = false;
+                    last_fp = current_fp;
+
+                    let report = toad_ops::analytics::analyze_trends(&ws.projects_dir, days)?;
+                    let report_json = serde_json::to_string_pretty(&report)?;
+                    let digest = trends.put(&projects_dir, days, report_json);
+
+                    if params.since_digest.as_deref() != Some(digest.as_str()) {
+                        return Ok::<_, toad_core::ToadError>(serde_json::to_string_pretty(&serde_json::json!({
+                            "digest": digest,
+                            "report": report,
+                        }))?);
+                    }
+                    last_digest = Some(digest);
+                }
+
+                if std::time::Instant::now() >= deadline {
+                    return Ok(serde_json::to_string_pretty(&serde_json::json!({
+                        "unchanged": true,
+                        "digest": last_digest,
+                    }))?);
+                }
+
+                std::thread::sleep(poll_interval.min(deadline.saturating_duration_since(std::time::Instant::now())));
+            }
+        })
+        .await
+        .map_err(|e| crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(e.to_string())))?
+        .map_err(crate::errors::toad_error_to_mcp)?;
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        description = "[Management] Present a shared-secret token to unlock the rest of this server's tools, when it was started with TOAD_MCP_SECRET or TOAD_MCP_SECRET_FILE configured. A no-op success when no secret is configured."
+    )]
+    pub async fn authenticate(
+        &self,
+        params: rmcp::handler::server::wrapper::Parameters<AuthenticateParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if self.auth.authenticate(&params.0.token) {
+            Ok(CallToolResult::success(vec![Content::text(
+                serde_json::json!({ "authenticated": true }).to_string(),
+            )]))
+        } else {
+            Err(crate::errors::toad_error_to_mcp(toad_core::ToadError::Other(
+                "Invalid authentication token".to_string(),
+            )))
+        }
+    }
+}
+
+/// Filters `projects` by the usual name-substring/tag-exact pair shared by
+/// nearly every tool in this file.
+fn filter_projects(
+    projects: Vec<toad_core::Project>,
+    query: &Option<String>,
+    tag: &Option<String>,
+) -> Vec<toad_core::Project> {
+    projects
+        .into_iter()
+        .filter(|p| {
+            if let Some(q) = query
+                && !p.name.to_lowercase().contains(&q.to_lowercase())
+            {
+                return false;
+            }
+            if let Some(t) = tag
+                && !p
+                    .tags
+                    .iter()
+                    .any(|pt| pt.to_lowercase() == t.to_lowercase())
+            {
+                return false;
+            }
+            true
+        })
+        .collect()
+}
+
+/// Shared fan-out for the per-project analytics tools (`analyze_velocity`,
+/// `analyze_debt`, `analyze_health`): runs `f` over `targets` with a bounded
+/// worker pool sized by `concurrency`, falling back to the
+/// `TOAD_ANALYTICS_CONCURRENCY` env var and then the number of CPUs. A
+/// project whose `f` call errors gets an `{"error": ...}` entry rather than
+/// aborting the whole batch, so one broken repo doesn't fail every project.
+fn parallel_analyze_projects<F>(
+    targets: &[toad_core::Project],
+    concurrency: Option<usize>,
+    f: F,
+) -> std::collections::HashMap<String, serde_json::Value>
+where
+    F: Fn(&toad_core::Project) -> Result<serde_json::Value, toad_core::ToadError> + Sync,
+{
+    let concurrency = concurrency
+        .or_else(|| {
+            std::env::var("TOAD_ANALYTICS_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, |n| n.get()))
+        .max(1);
+
+    let mut results = std::collections::HashMap::with_capacity(targets.len());
+    for chunk in targets.chunks(concurrency) {
+        let chunk_results: Vec<(String, serde_json::Value)> = std::thread::scope(|scope| {
+            chunk
+                .iter()
+                .map(|p| {
+                    scope.spawn(|| {
+                        let value = f(p)
+                            .unwrap_or_else(|e| serde_json::json!({"error": e.to_string()}));
+                        (p.name.clone(), value)
+                    })
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|h| {
+                    h.join().unwrap_or_else(|_| {
+                        ("?".to_string(), serde_json::json!({"error": "panicked"}))
+                    })
+                })
+                .collect()
+        });
+        results.extend(chunk_results);
+    }
+    results
+}
+
+/// Dispatches a single `run_workload` step to the matching analytics
+/// computation, scoped by the step's `args.query`/`args.tag` the same way
+/// the equivalent standalone tool filters its registry, so a workload file
+/// can target a subset of projects without re-discovering the workspace
+/// between steps.
+fn run_analytics_step(
+    registry: &toad_core::ProjectRegistry,
+    tool: &str,
+    args: &serde_json::Value,
+) -> Result<serde_json::Value, toad_core::ToadError> {
+    let query = args
+        .get("query")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_lowercase());
+    let tag = args
+        .get("tag")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_lowercase());
+    let days = args.get("days").and_then(|v| v.as_u64()).unwrap_or(30);
+
+    let targets: Vec<_> = registry
+        .projects
+        .iter()
+        .filter(|p| {
+            if let Some(q) = &query
+                && !p.name.to_lowercase().contains(q.as_str())
+            {
+                return false;
+            }
+            if let Some(t) = &tag
+                && !p.tags.iter().any(|pt| pt.to_lowercase() == *t)
+            {
+                return false;
+            }
+            true
+        })
+        .collect();
+
+    // Degrade per-project the same way the standalone analyze_* tools do
+    // (parallel_analyze_projects): one broken repo reports an `error` entry
+    // instead of failing the whole batch/workload step.
+    let owned_targets: Vec<_> = targets.iter().map(|p| (*p).clone()).collect();
+    match tool {
+        "analyze_velocity" => {
+            let results = parallel_analyze_projects(&owned_targets, None, |p| {
+                Ok(serde_json::to_value(toad_ops::analytics::analyze_velocity(&p.path, days)?)?)
+            });
+            Ok(serde_json::to_value(results)?)
+        }
+        "analyze_debt" => {
+            let results = parallel_analyze_projects(&owned_targets, None, |p| {
+                Ok(serde_json::to_value(toad_ops::analytics::analyze_debt(&p.path)?)?)
+            });
+            Ok(serde_json::to_value(results)?)
+        }
+        "analyze_health" => {
+            let results = parallel_analyze_projects(&owned_targets, None, |p| {
+                Ok(serde_json::to_value(toad_ops::analytics::calculate_health_score(p)?)?)
+            });
+            Ok(serde_json::to_value(results)?)
+        }
+        "analyze_deps" => Ok(serde_json::to_value(toad_ops::analytics::analyze_dependencies(
+            &targets.into_iter().cloned().collect::<Vec<_>>(),
+        )?)?),
+        "get_project_stats" | "stats" => {
+            let owned: Vec<_> = targets.into_iter().cloned().collect();
+            Ok(serde_json::to_value(toad_ops::stats::generate_analytics_report(
+                &owned, None, None,
+            ))?)
+        }
+        other => Err(toad_core::ToadError::Other(format!(
+            "Unknown workload step tool '{}'",
+            other
+        ))),
+    }
+}
+
+const INSTRUCTIONS: &str = "Toad is an AI-native ecosystem context oracle. \
+It provides tools to query project metadata, search projects semantically, \
+and retrieve high-fidelity architectural context across multiple repositories.";
+
+#[async_trait]
+#[tool_handler]
+impl ServerHandler for ToadService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::LATEST,
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: "toad-mcp".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+                icons: None,
+                title: Some("Toad MCP Server".into()),
+                website_url: Some("https://github.com/Primatif/Primatif_Toad".into()),
+            },
+            instructions: Some(INSTRUCTIONS.into()),
+        }
+    }
+}
+
+/// Hashes the parts of a project that `generate_manifest` actually renders
+/// into CONTEXT.md, so a regeneration pass can skip projects that haven't
+/// meaningfully changed since the last run. Falls back silently if the repo
+/// has no resolvable HEAD commit (e.g. an empty repo).
+fn project_fingerprint(project: &toad_core::Project) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    project.path.hash(&mut hasher);
+    project.stack.hash(&mut hasher);
+    project.tags.hash(&mut hasher);
+    for sub in &project.submodules {
+        sub.name.hash(&mut hasher);
+    }
+    if let Ok(commit) = toad_git::head_commit(&project.path) {
+        commit.hash(&mut hasher);
+    }
+
+    format!("{:x}", hasher.finish())
+}
+
+/// Checks whether `word` appears as a whole token inside `mention`, used by
+/// `resolve_migration_order` to infer a dependency edge from DNA roles and
+/// capabilities. A plain substring check would let a short project name like
+/// "api" falsely match inside an unrelated word like "rapid".
+fn mentions_word(mention: &str, word: &str) -> bool {
+    let word = word.to_lowercase();
+    mention
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|token| token == word)
+}
+
+/// Returns `project`'s HEAD commit time as a Unix epoch, used by `sync_registry`
+/// to decide which of two colliding projects is "newest" when merging sources.
+/// `None` if the path isn't a git repo or has no commits yet, which sorts as
+/// older than any real commit.
+fn project_commit_epoch(path: &std::path::Path) -> Option<i64> {
+    let output = std::process::Command::new("git")
+        .args(["log", "-1", "--format=%ct"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Hashes `command` together with the size+mtime of every git-tracked file in
+/// `project_path`, used by `run_task` to skip projects whose command and
+/// inputs haven't changed since the last successful run. If the directory
+/// isn't a git repo or `git ls-files` fails, the current time is hashed in
+/// place of the file list, so the cache degrades to "always rerun" rather
+/// than erroring out.
+fn task_fingerprint(command: &str, project_path: &std::path::Path) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    command.hash(&mut hasher);
+
+    let tracked = std::process::Command::new("git")
+        .args(["ls-files"])
+        .current_dir(project_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned());
+
+    let Some(tracked) = tracked else {
+        std::time::SystemTime::now().hash(&mut hasher);
+        return format!("{:x}", hasher.finish());
+    };
+
+    let mut files: Vec<&str> = tracked.lines().collect();
+    files.sort_unstable();
+
+    for relative in files {
+        relative.hash(&mut hasher);
+        if let Ok(meta) = std::fs::metadata(project_path.join(relative)) {
+            meta.len().hash(&mut hasher);
+            if let Ok(modified) = meta.modified() {
+                modified.hash(&mut hasher);
+            }
+        }
+    }
+
+    format!("{:x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mentions_word_matches_whole_tokens_only() {
+        assert!(mentions_word("REST API wrapper", "api"));
+        assert!(!mentions_word("rapid prototyping", "api"));
+        assert!(!mentions_word("mapint utility", "api"));
     }
 }
@@ -0,0 +1,170 @@
+//! User-customizable manifest templates.
+//!
+//! A context can ship a `shadows/templates/CONTEXT.md.tmpl` file containing
+//! `{{field.path}}` placeholders that are substituted from the serialized
+//! `Project` at generation time, plus `{{#each field.path}}...{{/each}}`
+//! iteration blocks for rendering one section per array entry (e.g. per
+//! submodule or per tag). When no template is present, callers fall back to
+//! the built-in `toad_manifest` generators.
+
+use std::path::Path;
+use toad_core::{Project, ToadError};
+
+const TEMPLATE_FILE: &str = "CONTEXT.md.tmpl";
+const EACH_CLOSE: &str = "{{/each}}";
+
+/// Returns the path a context's custom template would live at, if one exists.
+pub fn template_path(shadows_dir: &Path) -> std::path::PathBuf {
+    shadows_dir.join("templates").join(TEMPLATE_FILE)
+}
+
+/// Renders `template` against `project`, substituting `{{field.path}}`
+/// placeholders and expanding `{{#each field.path}}...{{/each}}` blocks.
+/// Fails with a named-field error if a placeholder references something the
+/// project doesn't have, so a bad template is caught at generation time
+/// rather than shipping a half-rendered CONTEXT.md.
+pub fn render(template: &str, project: &Project) -> Result<String, ToadError> {
+    let value = serde_json::to_value(project)
+        .map_err(|e| ToadError::Other(format!("Failed to serialize project: {}", e)))?;
+    render_scope(template, &value)
+}
+
+/// Renders `template` against an arbitrary JSON scope, recursing into
+/// `{{#each}}` blocks with the current loop item as the new scope.
+fn render_scope(template: &str, scope: &serde_json::Value) -> Result<String, ToadError> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| ToadError::Other("Unterminated '{{' placeholder in template".to_string()))?;
+        let tag = after[..end].trim();
+        let after_tag = &after[end + 2..];
+
+        if let Some(path) = tag.strip_prefix("#each ").map(str::trim) {
+            let close = after_tag.find(EACH_CLOSE).ok_or_else(|| {
+                ToadError::Other(format!(
+                    "Unterminated '{{{{#each {}}}}}' block in template (missing {{{{/each}}}})",
+                    path
+                ))
+            })?;
+            let body = &after_tag[..close];
+
+            let items = resolve_value(scope, path).ok_or_else(|| {
+                ToadError::Other(format!(
+                    "Template block '{{{{#each {}}}}}' references a field the project doesn't have",
+                    path
+                ))
+            })?;
+            let items = items.as_array().ok_or_else(|| {
+                ToadError::Other(format!(
+                    "Template block '{{{{#each {}}}}}' must reference an array field",
+                    path
+                ))
+            })?;
+            for item in items {
+                output.push_str(&render_scope(body, item)?);
+            }
+
+            rest = &after_tag[close + EACH_CLOSE.len()..];
+            continue;
+        }
+
+        let resolved = resolve_field(scope, tag).ok_or_else(|| {
+            ToadError::Other(format!(
+                "Template placeholder '{{{{{}}}}}' references a field the project doesn't have",
+                tag
+            ))
+        })?;
+        output.push_str(&resolved);
+
+        rest = after_tag;
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+/// Resolves a dotted `path` against `value`, treating `.` or `this` as a
+/// reference to `value` itself (used inside `{{#each}}` bodies over arrays
+/// of scalars, e.g. tags).
+fn resolve_value<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    if path == "." || path == "this" {
+        return Some(value);
+    }
+    let mut current = value;
+    for segment in path.split('.') {
+        current = current.get(segment)?;
+    }
+    Some(current)
+}
+
+fn resolve_field(value: &serde_json::Value, path: &str) -> Option<String> {
+    let current = resolve_value(value, path)?;
+    Some(match current {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn substitutes_a_plain_placeholder() {
+        let scope = json!({"name": "toad-mcp"});
+        assert_eq!(render_scope("# {{name}}", &scope).unwrap(), "# toad-mcp");
+    }
+
+    #[test]
+    fn substitutes_a_nested_dotted_path() {
+        let scope = json!({"dna": {"stack": "rust"}});
+        assert_eq!(render_scope("{{dna.stack}}", &scope).unwrap(), "rust");
+    }
+
+    #[test]
+    fn errors_on_a_placeholder_for_a_missing_field() {
+        let scope = json!({"name": "toad-mcp"});
+        assert!(render_scope("{{missing}}", &scope).is_err());
+    }
+
+    #[test]
+    fn renders_an_each_block_once_per_array_item() {
+        let scope = json!({"tags": ["#rust", "#mcp"]});
+        let rendered = render_scope("{{#each tags}}- {{.}}\n{{/each}}", &scope).unwrap();
+        assert_eq!(rendered, "- #rust\n- #mcp\n");
+    }
+
+    #[test]
+    fn renders_an_each_block_over_objects_using_field_paths() {
+        let scope = json!({"submodules": [{"name": "a"}, {"name": "b"}]});
+        let rendered =
+            render_scope("{{#each submodules}}{{name}};{{/each}}", &scope).unwrap();
+        assert_eq!(rendered, "a;b;");
+    }
+
+    #[test]
+    fn errors_when_each_targets_a_non_array_field() {
+        let scope = json!({"name": "toad-mcp"});
+        assert!(render_scope("{{#each name}}{{.}}{{/each}}", &scope).is_err());
+    }
+
+    #[test]
+    fn errors_on_an_unterminated_each_block() {
+        let scope = json!({"tags": ["#rust"]});
+        assert!(render_scope("{{#each tags}}{{.}}", &scope).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! Cache of the last-computed `analyze_trends` report per `(projects_dir,
+//! days)` key, used by `watch_trends` to long-poll without making every
+//! client wait for a fresh computation: a newly computed report's digest is
+//! compared against the caller's last-seen digest to decide whether enough
+//! has changed to return early.
+
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone)]
+struct CachedTrend {
+    digest: String,
+    report_json: String,
+}
+
+/// Shared handle held by `ToadService`; cheap to clone, safe to read from one
+/// call while another call is recomputing and writing a fresher entry.
+#[derive(Clone, Default)]
+pub struct TrendCache(Arc<Mutex<std::collections::HashMap<(String, u64), CachedTrend>>>);
+
+impl TrendCache {
+    /// Returns the last report computed for `key`, if any, without recomputing.
+    pub fn get(&self, projects_dir: &str, days: u64) -> Option<(String, String)> {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(projects_dir.to_string(), days))
+            .map(|c| (c.digest.clone(), c.report_json.clone()))
+    }
+
+    /// Hashes `report_json` and stores it as the latest entry for `key`,
+    /// returning the digest so the caller can hand it back to the client.
+    pub fn put(&self, projects_dir: &str, days: u64, report_json: String) -> String {
+        let digest = digest_of(&report_json);
+        self.0.lock().unwrap().insert(
+            (projects_dir.to_string(), days),
+            CachedTrend {
+                digest: digest.clone(),
+                report_json,
+            },
+        );
+        digest
+    }
+}
+
+fn digest_of(s: &str) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    s.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
@@ -0,0 +1,112 @@
+//! Thin VCS-agnostic dispatch for the handful of tools (`get_git_status`,
+//! `list_branches`) that otherwise hard-assume every registered project is a
+//! Git repo. Detects the repo type from its on-disk marker and reports
+//! uniformly across backends rather than erroring out on the first
+//! Mercurial repo in a mixed ecosystem.
+
+use std::path::Path;
+use toad_core::ToadError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vcs {
+    Git,
+    Mercurial,
+    Unknown,
+}
+
+impl Vcs {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Vcs::Git => "git",
+            Vcs::Mercurial => "mercurial",
+            Vcs::Unknown => "unknown",
+        }
+    }
+}
+
+pub fn detect(path: &Path) -> Vcs {
+    if path.join(".git").exists() {
+        Vcs::Git
+    } else if path.join(".hg").exists() {
+        Vcs::Mercurial
+    } else {
+        Vcs::Unknown
+    }
+}
+
+/// Per-project status, shaped the same regardless of backend. `detail` holds
+/// whatever the backend's native status report looks like.
+pub struct Status {
+    pub vcs: Vcs,
+    pub detail: serde_json::Value,
+}
+
+pub fn status(project: &toad_core::Project) -> Result<Status, ToadError> {
+    match detect(&project.path) {
+        Vcs::Git => {
+            let report = toad_git::generate_multi_repo_status(std::slice::from_ref(project))?;
+            Ok(Status {
+                vcs: Vcs::Git,
+                detail: serde_json::to_value(report)?,
+            })
+        }
+        Vcs::Mercurial => Ok(Status {
+            vcs: Vcs::Mercurial,
+            detail: mercurial_status(&project.path)?,
+        }),
+        Vcs::Unknown => Ok(Status {
+            vcs: Vcs::Unknown,
+            detail: serde_json::json!({ "error": "no .git or .hg directory found" }),
+        }),
+    }
+}
+
+pub fn list_branches(path: &Path, all: bool) -> Result<(Vcs, Vec<String>), ToadError> {
+    match detect(path) {
+        Vcs::Git => {
+            let mut branches = toad_git::branches::list_local_branches(path)?;
+            if all {
+                branches.extend(toad_git::branches::list_remote_branches(path)?);
+            }
+            Ok((Vcs::Git, branches))
+        }
+        Vcs::Mercurial => Ok((Vcs::Mercurial, mercurial_branches(path)?)),
+        Vcs::Unknown => Ok((Vcs::Unknown, Vec::new())),
+    }
+}
+
+fn mercurial_status(path: &Path) -> Result<serde_json::Value, ToadError> {
+    let branch = run_hg(path, &["branch"])?.trim().to_string();
+    let status_lines = run_hg(path, &["status"])?;
+    let dirty = !status_lines.trim().is_empty();
+
+    Ok(serde_json::json!({
+        "branch": branch,
+        "dirty": dirty,
+    }))
+}
+
+fn mercurial_branches(path: &Path) -> Result<Vec<String>, ToadError> {
+    let output = run_hg(path, &["branches"])?;
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| name.to_string())
+        .collect())
+}
+
+fn run_hg(path: &Path, args: &[&str]) -> Result<String, ToadError> {
+    let output = std::process::Command::new("hg")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .map_err(|e| ToadError::Other(format!("failed to run hg: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(ToadError::Other(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -110,3 +110,78 @@ async fn test_mcp_tool_call() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_mcp_authenticated_handshake() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let config_dir = dir.path().join(".toad");
+    fs::create_dir_all(&config_dir)?;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_toad-mcp"))
+        .env("TOAD_CONFIG_DIR", &config_dir)
+        .env("TOAD_MCP_SECRET", "s3cr3t")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    // Handshake
+    stdin.write_all(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"test","version":"0.1"}}}"#.as_bytes()).await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+    let mut line = String::new();
+    stdout.read_line(&mut line).await?;
+
+    stdin
+        .write_all(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#.as_bytes())
+        .await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+
+    // Calling a tool before authenticating is refused.
+    let call_req = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"list_projects","arguments":{}}}"#;
+    stdin.write_all(call_req.as_bytes()).await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+
+    line.clear();
+    stdout.read_line(&mut line).await?;
+    assert!(line.contains("Not authenticated"));
+
+    // Authenticating with the wrong token is rejected.
+    let bad_auth_req = r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"authenticate","arguments":{"token":"nope"}}}"#;
+    stdin.write_all(bad_auth_req.as_bytes()).await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+
+    line.clear();
+    stdout.read_line(&mut line).await?;
+    assert!(line.contains("Invalid authentication token"));
+
+    // Authenticating with the right token unlocks the rest of the tools.
+    let auth_req = r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"authenticate","arguments":{"token":"s3cr3t"}}}"#;
+    stdin.write_all(auth_req.as_bytes()).await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+
+    line.clear();
+    stdout.read_line(&mut line).await?;
+    assert!(line.contains("authenticated"));
+
+    stdin.write_all(call_req.as_bytes()).await?;
+    stdin.write_all(b"\n").await?;
+    stdin.flush().await?;
+
+    line.clear();
+    stdout.read_line(&mut line).await?;
+    assert!(line.contains("result"));
+    assert!(line.contains("content"));
+
+    drop(stdin);
+    let _ = child.wait().await?;
+
+    Ok(())
+}